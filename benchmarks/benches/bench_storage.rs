@@ -59,6 +59,52 @@ fn accumulator_append(c: &mut Criterion) {
     });
 }
 
+/// Proofs of all leaves of a 1000 leaves accumulator, batched and one by one.
+fn accumulator_get_proofs(c: &mut Criterion) {
+    ::starcoin_logger::init_for_test();
+    let path = starcoin_config::temp_dir();
+    let storage = Arc::new(
+        Storage::new(StorageInstance::new_cache_and_db_instance(
+            CacheStorage::new(None),
+            DBStorage::new(path.as_ref(), RocksdbConfig::default(), None).unwrap(),
+        ))
+        .unwrap(),
+    );
+    let accumulator = MerkleAccumulator::new_with_info(
+        AccumulatorInfo::default(),
+        Arc::new(storage.get_transaction_accumulator_storage()),
+    );
+    accumulator.append(&create_leaves(0..1000)).unwrap();
+    accumulator.flush().unwrap();
+    let info = accumulator.get_info();
+    let leaf_indices: Vec<u64> = (0..1000).collect();
+    let new_accumulator = || {
+        MerkleAccumulator::new_with_info(
+            info.clone(),
+            Arc::new(storage.get_transaction_accumulator_storage()),
+        )
+    };
+    c.bench_function("accumulator_get_proofs_batch", |b| {
+        b.iter_batched(
+            new_accumulator,
+            |bench| bench.get_proofs(1000, &leaf_indices).unwrap(),
+            BatchSize::LargeInput,
+        )
+    });
+    c.bench_function("accumulator_get_proofs_single", |b| {
+        b.iter_batched(
+            new_accumulator,
+            |bench| {
+                leaf_indices
+                    .iter()
+                    .map(|leaf_index| bench.get_proof(*leaf_index).unwrap().unwrap())
+                    .collect::<Vec<_>>()
+            },
+            BatchSize::LargeInput,
+        )
+    });
+}
+
 fn create_leaves(nums: std::ops::Range<usize>) -> Vec<HashValue> {
     nums.map(|x| HashValue::sha3_256_of(x.to_be_bytes().as_ref()))
         .collect()
@@ -68,12 +114,13 @@ criterion_group!(
     name=starcoin_storage_benches;
     config = Criterion::default()
     .with_profiler(PProfProfiler::new(100, Output::Flamegraph(None)));
-    targets=storage_transaction, accumulator_append
+    targets=storage_transaction, accumulator_append, accumulator_get_proofs
 );
 #[cfg(not(target_os = "linux"))]
 criterion_group!(
     starcoin_storage_benches,
     storage_transaction,
-    accumulator_append
+    accumulator_append,
+    accumulator_get_proofs
 );
 criterion_main!(starcoin_storage_benches);
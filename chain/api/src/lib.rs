@@ -12,6 +12,7 @@ mod chain;
 mod errors;
 pub mod message;
 mod service;
+mod throughput;
 
 #[derive(Clone, Debug)]
 pub struct ExcludedTxns {
@@ -26,6 +27,29 @@ use starcoin_crypto::hash::PlainCryptoHash;
 use starcoin_crypto::HashValue;
 use starcoin_vm_types::access_path::AccessPath;
 use starcoin_vm_types::contract_event::ContractEvent;
+pub use throughput::ThroughputWindow;
+
+/// Whether a block seen before is still on the main chain, see `ChainAsyncService::detect_reorg`.
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub enum ReorgStatus {
+    NoReorg,
+    /// The block was reorged out, the blocks after `common_ancestor` should be rolled back.
+    Reorged {
+        common_ancestor: u64,
+    },
+}
+
+/// The number of entries read by `ChainAsyncService::preheat_caches`.
+#[derive(Debug, Default, Eq, PartialEq, Clone, Serialize, Deserialize)]
+pub struct PreheatReport {
+    pub block_accumulator_nodes: u64,
+    pub txn_accumulator_nodes: u64,
+    pub block_headers: u64,
+    pub block_infos: u64,
+    pub transaction_infos: u64,
+    /// The budget ran out before all the `depth` blocks were read.
+    pub budget_exhausted: bool,
+}
 
 #[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
 pub struct EventWithProof {
@@ -1,7 +1,7 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2
 
-use crate::TransactionInfoWithProof;
+use crate::{PreheatReport, ReorgStatus, TransactionInfoWithProof};
 use anyhow::Result;
 use starcoin_crypto::HashValue;
 use starcoin_service_registry::ServiceRequest;
@@ -14,6 +14,8 @@ use starcoin_types::{
     transaction::Transaction,
 };
 use starcoin_vm_types::access_path::AccessPath;
+use starcoin_vm_types::gas_schedule::{CostTable, GasScheduleDiff};
+use std::time::Duration;
 
 #[allow(clippy::large_enum_variant)]
 #[derive(Clone, Debug)]
@@ -60,6 +62,17 @@ pub enum ChainRequest {
         access_path: Option<AccessPath>,
     },
     GetBlockInfos(Vec<HashValue>),
+    GetGasScheduleByNumber(BlockNumber),
+    PreheatCaches {
+        depth: u64,
+        budget: u64,
+    },
+    DetectReorg {
+        known_number: BlockNumber,
+        known_root: HashValue,
+    },
+    EstimatedTimeRemaining(),
+    GetGasScheduleChange(),
 }
 
 impl ServiceRequest for ChainRequest {
@@ -88,4 +101,9 @@ pub enum ChainResponse {
     HashVec(Vec<HashValue>),
     TransactionProof(Box<Option<TransactionInfoWithProof>>),
     BlockInfoVec(Box<Vec<Option<BlockInfo>>>),
+    GasSchedule(Box<CostTable>),
+    PreheatReport(PreheatReport),
+    ReorgStatus(ReorgStatus),
+    Duration(Option<Duration>),
+    GasScheduleDiff(Option<Box<GasScheduleDiff>>),
 }
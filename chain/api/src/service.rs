@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2
 
 use crate::message::{ChainRequest, ChainResponse};
-use crate::TransactionInfoWithProof;
+use crate::{PreheatReport, ReorgStatus, TransactionInfoWithProof};
 use anyhow::{bail, Result};
 use starcoin_crypto::HashValue;
 use starcoin_service_registry::{ActorService, ServiceHandler, ServiceRef};
@@ -15,6 +15,8 @@ use starcoin_types::{
     startup_info::StartupInfo,
 };
 use starcoin_vm_types::access_path::AccessPath;
+use starcoin_vm_types::gas_schedule::{CostTable, GasScheduleDiff};
+use std::time::Duration;
 
 /// Readable block chain service trait
 pub trait ReadableChainService {
@@ -72,6 +74,23 @@ pub trait ReadableChainService {
     ) -> Result<Option<TransactionInfoWithProof>>;
 
     fn get_block_infos(&self, ids: Vec<HashValue>) -> Result<Vec<Option<BlockInfo>>>;
+
+    /// Get the gas schedule used to execute the main block at `number`.
+    fn gas_schedule_at(&self, number: BlockNumber) -> Result<CostTable>;
+
+    /// Load the index caches of the block and transaction accumulators, then read the headers,
+    /// block infos and transaction infos of the top `depth` main blocks, so the storage caches
+    /// are populated too. At most `budget` entries are read in total.
+    fn preheat_caches(&self, depth: u64, budget: u64) -> Result<PreheatReport>;
+
+    fn detect_reorg(&self, known_number: BlockNumber, known_root: HashValue)
+        -> Result<ReorgStatus>;
+
+    fn estimated_time_remaining(&self) -> Result<Option<Duration>>;
+
+    /// The gas schedule changes between the main head block and its parent block, None if both
+    /// are executed with the same schedule.
+    fn gas_schedule_change(&self) -> Result<Option<GasScheduleDiff>>;
 }
 
 /// Writeable block chain service trait
@@ -139,6 +158,32 @@ pub trait ChainAsyncService:
     ) -> Result<Option<TransactionInfoWithProof>>;
 
     async fn get_block_infos(&self, hashes: Vec<HashValue>) -> Result<Vec<Option<BlockInfo>>>;
+
+    /// Get the gas schedule used to execute the main block at `block_number`,
+    /// return error if the block or its state is not found.
+    async fn gas_schedule_at(&self, block_number: BlockNumber) -> Result<CostTable>;
+
+    /// Warm the caches with the accumulators and the top `depth` main blocks, reading at most
+    /// `budget` entries. Both are capped by the service.
+    async fn preheat_caches(&self, depth: u64, budget: u64) -> Result<PreheatReport>;
+
+    /// Check whether the block at `known_number`, seen with block accumulator root `known_root`,
+    /// is still on the main chain, and find the common ancestor if it was reorged out.
+    async fn detect_reorg(
+        &self,
+        known_number: BlockNumber,
+        known_root: HashValue,
+    ) -> Result<ReorgStatus>;
+
+    /// The estimated time to catch up with the sync target, from the blocks per second over a
+    /// sliding window of the recent head blocks and the remaining block gap. None if the
+    /// throughput or the target is unknown.
+    async fn estimated_time_remaining(&self) -> Result<Option<Duration>>;
+
+    /// The gas schedule changes activated by the main head block, from the schedule of its parent
+    /// block. None if the head block did not change the schedule. The service logs each change
+    /// once, when the block becomes the head.
+    async fn on_schedule_change_log(&self) -> Result<Option<GasScheduleDiff>>;
 }
 
 #[async_trait::async_trait]
@@ -436,4 +481,62 @@ where
             bail!("get block_infos error")
         }
     }
+
+    async fn gas_schedule_at(&self, block_number: BlockNumber) -> Result<CostTable> {
+        let response = self
+            .send(ChainRequest::GetGasScheduleByNumber(block_number))
+            .await??;
+        if let ChainResponse::GasSchedule(gas_schedule) = response {
+            Ok(*gas_schedule)
+        } else {
+            bail!("get gas schedule error")
+        }
+    }
+
+    async fn preheat_caches(&self, depth: u64, budget: u64) -> Result<PreheatReport> {
+        let response = self
+            .send(ChainRequest::PreheatCaches { depth, budget })
+            .await??;
+        if let ChainResponse::PreheatReport(report) = response {
+            Ok(report)
+        } else {
+            bail!("preheat caches error")
+        }
+    }
+
+    async fn detect_reorg(
+        &self,
+        known_number: BlockNumber,
+        known_root: HashValue,
+    ) -> Result<ReorgStatus> {
+        let response = self
+            .send(ChainRequest::DetectReorg {
+                known_number,
+                known_root,
+            })
+            .await??;
+        if let ChainResponse::ReorgStatus(status) = response {
+            Ok(status)
+        } else {
+            bail!("detect reorg error")
+        }
+    }
+
+    async fn estimated_time_remaining(&self) -> Result<Option<Duration>> {
+        let response = self.send(ChainRequest::EstimatedTimeRemaining()).await??;
+        if let ChainResponse::Duration(duration) = response {
+            Ok(duration)
+        } else {
+            bail!("get estimated time remaining error")
+        }
+    }
+
+    async fn on_schedule_change_log(&self) -> Result<Option<GasScheduleDiff>> {
+        let response = self.send(ChainRequest::GetGasScheduleChange()).await??;
+        if let ChainResponse::GasScheduleDiff(diff) = response {
+            Ok(diff.map(|diff| *diff))
+        } else {
+            bail!("get gas schedule change error")
+        }
+    }
 }
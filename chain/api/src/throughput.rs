@@ -0,0 +1,80 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// A bounded window of the recent `(Instant, processed)` samples, to estimate the recent
+/// throughput of a process, e.g. the blocks connected to the main chain.
+#[derive(Clone, Debug)]
+pub struct ThroughputWindow {
+    samples: VecDeque<(Instant, u64)>,
+    max_samples: usize,
+}
+
+impl ThroughputWindow {
+    /// Create a window keeping at most `max_samples` samples, at least two are needed for a rate.
+    pub fn new(max_samples: usize) -> Self {
+        let max_samples = max_samples.max(2);
+        Self {
+            samples: VecDeque::with_capacity(max_samples),
+            max_samples,
+        }
+    }
+
+    /// Record the total processed count at `at`, the oldest sample is dropped when the window
+    /// is full. A count lower than the last one, e.g. after the main chain switched to a shorter
+    /// branch, restarts the window.
+    pub fn record(&mut self, at: Instant, processed: u64) {
+        if let Some((_, last)) = self.samples.back() {
+            if processed < *last {
+                self.samples.clear();
+            }
+        }
+        if self.samples.len() >= self.max_samples {
+            self.samples.pop_front();
+        }
+        self.samples.push_back((at, processed));
+    }
+
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// The processed items per second over the window, None if there are less than two samples
+    /// or no time passed between them.
+    pub fn rate(&self) -> Option<f64> {
+        let (first_at, first) = self.samples.front()?;
+        let (last_at, last) = self.samples.back()?;
+        let elapsed = last_at.checked_duration_since(*first_at)?.as_secs_f64();
+        if elapsed <= 0f64 {
+            return None;
+        }
+        Some(last.saturating_sub(*first) as f64 / elapsed)
+    }
+
+    /// The time to process `remaining` items at the window rate, None if the rate is unknown or
+    /// zero.
+    pub fn estimated_time_remaining(&self, remaining: u64) -> Option<Duration> {
+        if remaining == 0 {
+            return Some(Duration::ZERO);
+        }
+        let rate = self.rate()?;
+        if rate <= 0f64 {
+            return None;
+        }
+        let seconds = remaining as f64 / rate;
+        if !seconds.is_finite() || seconds >= u64::MAX as f64 {
+            return Some(Duration::MAX);
+        }
+        Some(Duration::from_secs_f64(seconds))
+    }
+}
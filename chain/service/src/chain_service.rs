@@ -2,10 +2,12 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use anyhow::{format_err, Error, Result};
+use starcoin_accumulator::Accumulator;
 use starcoin_chain::BlockChain;
 use starcoin_chain_api::message::{ChainRequest, ChainResponse};
 use starcoin_chain_api::{
-    ChainReader, ChainWriter, ReadableChainService, TransactionInfoWithProof,
+    ChainReader, ChainWriter, PreheatReport, ReadableChainService, ReorgStatus, ThroughputWindow,
+    TransactionInfoWithProof,
 };
 use starcoin_config::NodeConfig;
 use starcoin_crypto::HashValue;
@@ -13,11 +15,13 @@ use starcoin_logger::prelude::*;
 use starcoin_service_registry::{
     ActorService, EventHandler, ServiceContext, ServiceFactory, ServiceHandler,
 };
+use starcoin_statedb::ChainStateDB;
 use starcoin_storage::{BlockStore, Storage, Store};
 use starcoin_types::block::ExecutedBlock;
 use starcoin_types::contract_event::ContractEventInfo;
 use starcoin_types::filter::Filter;
-use starcoin_types::system_events::NewHeadBlock;
+use starcoin_types::sync_status::SyncState;
+use starcoin_types::system_events::{NewHeadBlock, SyncStatusChangeEvent};
 use starcoin_types::transaction::RichTransactionInfo;
 use starcoin_types::{
     block::{Block, BlockHeader, BlockInfo, BlockNumber},
@@ -26,8 +30,19 @@ use starcoin_types::{
     transaction::Transaction,
 };
 use starcoin_vm_runtime::metrics::VMMetrics;
+use starcoin_vm_runtime::starcoin_vm::StarcoinVM;
 use starcoin_vm_types::access_path::AccessPath;
+use starcoin_vm_types::gas_schedule::{CostTable, GasScheduleDiff};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// The max number of blocks and of entries read by one `preheat_caches` request, the request
+/// is handled in the service loop, so it should not block other requests for long.
+pub const MAX_PREHEAT_DEPTH: u64 = 1000;
+pub const MAX_PREHEAT_BUDGET: u64 = 100_000;
+
+/// The number of recent head blocks used to estimate the sync throughput.
+pub const THROUGHPUT_WINDOW_SIZE: usize = 64;
 
 /// A Chain reader service to provider Reader API.
 pub struct ChainReaderService {
@@ -62,11 +77,13 @@ impl ServiceFactory<Self> for ChainReaderService {
 impl ActorService for ChainReaderService {
     fn started(&mut self, ctx: &mut ServiceContext<Self>) -> Result<()> {
         ctx.subscribe::<NewHeadBlock>();
+        ctx.subscribe::<SyncStatusChangeEvent>();
         Ok(())
     }
 
     fn stopped(&mut self, ctx: &mut ServiceContext<Self>) -> Result<()> {
         ctx.unsubscribe::<NewHeadBlock>();
+        ctx.unsubscribe::<SyncStatusChangeEvent>();
         Ok(())
     }
 }
@@ -81,6 +98,23 @@ impl EventHandler<Self, NewHeadBlock> for ChainReaderService {
         } {
             warn!("ChainReaderService handle NewHeadBlock err: {:?}", e);
         }
+        let head_number = self.inner.main.current_header().number();
+        self.inner.throughput.record(Instant::now(), head_number);
+        match self.inner.gas_schedule_change() {
+            Ok(Some(diff)) => info!("Gas schedule changed at block {}:\n{}", head_number, diff),
+            Ok(None) => {}
+            Err(e) => warn!("ChainReaderService load gas schedule change err: {:?}", e),
+        }
+    }
+}
+
+impl EventHandler<Self, SyncStatusChangeEvent> for ChainReaderService {
+    fn handle_event(
+        &mut self,
+        event: SyncStatusChangeEvent,
+        _ctx: &mut ServiceContext<ChainReaderService>,
+    ) {
+        self.inner.update_sync_target(event.0.sync_status());
     }
 }
 
@@ -232,6 +266,24 @@ impl ServiceHandler<Self, ChainRequest> for ChainReaderService {
             ChainRequest::GetBlockInfos(ids) => Ok(ChainResponse::BlockInfoVec(Box::new(
                 self.inner.get_block_infos(ids)?,
             ))),
+            ChainRequest::GetGasScheduleByNumber(number) => Ok(ChainResponse::GasSchedule(
+                Box::new(self.inner.gas_schedule_at(number)?),
+            )),
+            ChainRequest::PreheatCaches { depth, budget } => Ok(ChainResponse::PreheatReport(
+                self.inner.preheat_caches(depth, budget)?,
+            )),
+            ChainRequest::DetectReorg {
+                known_number,
+                known_root,
+            } => Ok(ChainResponse::ReorgStatus(
+                self.inner.detect_reorg(known_number, known_root)?,
+            )),
+            ChainRequest::EstimatedTimeRemaining() => Ok(ChainResponse::Duration(
+                self.inner.estimated_time_remaining()?,
+            )),
+            ChainRequest::GetGasScheduleChange() => Ok(ChainResponse::GasScheduleDiff(
+                self.inner.gas_schedule_change()?.map(Box::new),
+            )),
         }
     }
 }
@@ -242,6 +294,8 @@ pub struct ChainReaderServiceInner {
     main: BlockChain,
     storage: Arc<dyn Store>,
     vm_metrics: Option<VMMetrics>,
+    throughput: ThroughputWindow,
+    sync_target: Option<BlockNumber>,
 }
 
 impl ChainReaderServiceInner {
@@ -264,6 +318,8 @@ impl ChainReaderServiceInner {
             main,
             storage,
             vm_metrics,
+            throughput: ThroughputWindow::new(THROUGHPUT_WINDOW_SIZE),
+            sync_target: None,
         })
     }
 
@@ -286,6 +342,20 @@ impl ChainReaderServiceInner {
         )?;
         Ok(())
     }
+
+    /// Track the target block number while synchronizing, the throughput samples of a finished
+    /// sync are dropped so they do not skew the next one.
+    pub fn update_sync_target(&mut self, state: &SyncState) {
+        match state {
+            SyncState::Synchronizing { target, .. } => {
+                self.sync_target = Some(target.number);
+            }
+            _ => {
+                self.sync_target = None;
+                self.throughput.clear();
+            }
+        }
+    }
 }
 
 impl ReadableChainService for ChainReaderServiceInner {
@@ -416,14 +486,119 @@ impl ReadableChainService for ChainReaderServiceInner {
     fn get_block_infos(&self, ids: Vec<HashValue>) -> Result<Vec<Option<BlockInfo>>> {
         self.storage.get_block_infos(ids)
     }
+
+    fn gas_schedule_at(&self, number: BlockNumber) -> Result<CostTable> {
+        let header = self
+            .main_block_header_by_number(number)?
+            .ok_or_else(|| format_err!("Can not find block header by number {}", number))?;
+        // the transactions of a block are executed on the state of its parent block.
+        let state_root = if header.is_genesis() {
+            header.state_root()
+        } else {
+            self.storage
+                .get_block_header_by_hash(header.parent_hash())?
+                .ok_or_else(|| {
+                    format_err!("Can not find block header by hash {}", header.parent_hash())
+                })?
+                .state_root()
+        };
+        let statedb = ChainStateDB::new(self.storage.clone().into_super_arc(), Some(state_root));
+        let mut vm = StarcoinVM::new(self.vm_metrics.clone());
+        vm.load_configs(&statedb)?;
+        let gas_schedule = vm.get_gas_schedule().map_err(|status| {
+            format_err!("Load gas schedule at block {} failed: {:?}", number, status)
+        })?;
+        Ok(gas_schedule.clone())
+    }
+
+    fn preheat_caches(&self, depth: u64, budget: u64) -> Result<PreheatReport> {
+        let mut report = PreheatReport::default();
+        let depth = depth.min(MAX_PREHEAT_DEPTH);
+        let mut budget = budget.min(MAX_PREHEAT_BUDGET);
+        if depth == 0 || budget == 0 {
+            return Ok(report);
+        }
+        let block_accumulator = self.main.get_block_accumulator();
+        report.block_accumulator_nodes =
+            block_accumulator.warm_cache(block_accumulator.root_hash(), budget)? as u64;
+        budget -= report.block_accumulator_nodes;
+        let txn_accumulator = self.main.get_txn_accumulator();
+        report.txn_accumulator_nodes =
+            txn_accumulator.warm_cache(txn_accumulator.root_hash(), budget)? as u64;
+        budget -= report.txn_accumulator_nodes;
+
+        let head_number = self.main.current_header().number();
+        let block_ids = self.main.get_block_ids(head_number, true, depth)?;
+        for block_id in block_ids {
+            // a block reads its header, its info and its transaction infos.
+            if budget < 2 {
+                report.budget_exhausted = true;
+                break;
+            }
+            budget -= 2;
+            if self.storage.get_block_header_by_hash(block_id)?.is_some() {
+                report.block_headers += 1;
+            }
+            if self.storage.get_block_info(block_id)?.is_some() {
+                report.block_infos += 1;
+            }
+            let transaction_infos =
+                self.storage.get_block_transaction_infos(block_id)?.len() as u64;
+            report.transaction_infos += transaction_infos;
+            budget = budget.saturating_sub(transaction_infos);
+        }
+        debug!(
+            "Preheat caches with depth {}, {} of the budget left: {:?}",
+            depth, budget, report
+        );
+        Ok(report)
+    }
+
+    fn detect_reorg(
+        &self,
+        known_number: BlockNumber,
+        known_root: HashValue,
+    ) -> Result<ReorgStatus> {
+        self.main.detect_reorg(known_number, known_root)
+    }
+
+    fn estimated_time_remaining(&self) -> Result<Option<Duration>> {
+        let target = match self.sync_target {
+            Some(target) => target,
+            None => return Ok(None),
+        };
+        let remaining = target.saturating_sub(self.main.current_header().number());
+        Ok(self.throughput.estimated_time_remaining(remaining))
+    }
+
+    fn gas_schedule_change(&self) -> Result<Option<GasScheduleDiff>> {
+        let head_number = self.main.current_header().number();
+        if head_number == 0 {
+            return Ok(None);
+        }
+        let diff = self
+            .gas_schedule_at(head_number - 1)?
+            .diff(&self.gas_schedule_at(head_number)?);
+        Ok(if diff.is_empty() { None } else { Some(diff) })
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use starcoin_chain_api::ChainAsyncService;
-    use starcoin_config::NodeConfig;
+    use starcoin_config::{BuiltinNetworkID, ChainNetwork, ChainNetworkID, NodeConfig};
     use starcoin_service_registry::{RegistryAsyncService, RegistryService};
+    use starcoin_types::block::BlockIdAndNumber;
+    use starcoin_types::U256;
+    use starcoin_vm_types::gas_schedule::GasScheduleChange;
+    use starcoin_vm_types::genesis_config::StdlibVersion;
+    use starcoin_vm_types::on_chain_config::vm_config_type_tag;
+    use test_helper::dao::{
+        execute_script_on_chain_config, modify_on_chain_config_by_dao_block,
+        on_chain_config_type_tag, vote_vm_config_script,
+    };
+    use test_helper::Account;
 
     #[stest::test]
     async fn test_actor_launch() -> Result<()> {
@@ -437,4 +612,186 @@ mod tests {
         assert_eq!(&chain_status, chain_info.status());
         Ok(())
     }
+
+    #[stest::test(timeout = 120)]
+    fn test_gas_schedule_at() -> Result<()> {
+        // before stdlib 10, the gas schedule is the VMConfig resource, which a DAO proposal updates.
+        let mut genesis_config = BuiltinNetworkID::Test.genesis_config().clone();
+        genesis_config.stdlib_version = StdlibVersion::Version(9);
+        let net = ChainNetwork::new(
+            ChainNetworkID::Builtin(BuiltinNetworkID::Test),
+            genesis_config,
+        );
+        let mut vm_config = net.genesis_config().vm_config.clone();
+        vm_config
+            .gas_schedule
+            .gas_constants
+            .max_transaction_size_in_bytes += 1;
+        // the proposal is executed by the transaction of block 8.
+        let chain = modify_on_chain_config_by_dao_block(
+            Account::new(),
+            test_helper::gen_blockchain_for_test(&net)?,
+            &net,
+            vote_vm_config_script(&net, vm_config.clone()),
+            on_chain_config_type_tag(vm_config_type_tag()),
+            execute_script_on_chain_config(&net, vm_config_type_tag(), 0),
+        )?;
+        let inner = ChainReaderServiceInner::new(
+            Arc::new(NodeConfig::random_for_test()),
+            StartupInfo::new(chain.current_header().id()),
+            chain.get_storage(),
+            None,
+        )?;
+
+        let update_number = 8;
+        assert_eq!(
+            inner.gas_schedule_at(0)?,
+            net.genesis_config().vm_config.gas_schedule
+        );
+        assert_eq!(
+            inner.gas_schedule_at(update_number)?,
+            net.genesis_config().vm_config.gas_schedule
+        );
+        // the blocks after the update are executed with the new schedule.
+        assert_ne!(
+            inner.gas_schedule_at(update_number - 1)?,
+            inner.gas_schedule_at(update_number + 1)?
+        );
+        assert_eq!(
+            inner.gas_schedule_at(update_number + 1)?,
+            vm_config.gas_schedule
+        );
+        assert!(inner
+            .gas_schedule_at(chain.current_header().number() + 1)
+            .is_err());
+
+        // the head block executed with the new schedule reports the change from its parent.
+        let changed_header = chain
+            .get_header_by_number(update_number + 1)?
+            .expect("header should exist");
+        let changed_inner = ChainReaderServiceInner::new(
+            Arc::new(NodeConfig::random_for_test()),
+            StartupInfo::new(changed_header.id()),
+            chain.get_storage(),
+            None,
+        )?;
+        let diff = changed_inner
+            .gas_schedule_change()?
+            .expect("gas schedule should change");
+        assert_eq!(
+            diff.changes,
+            vec![GasScheduleChange {
+                name: "gas_constants.max_transaction_size_in_bytes".to_string(),
+                old_value: Some(
+                    vm_config
+                        .gas_schedule
+                        .gas_constants
+                        .max_transaction_size_in_bytes
+                        - 1
+                ),
+                new_value: Some(
+                    vm_config
+                        .gas_schedule
+                        .gas_constants
+                        .max_transaction_size_in_bytes
+                ),
+            }]
+        );
+        if chain.current_header().number() > update_number + 1 {
+            assert!(inner.gas_schedule_change()?.is_none());
+        }
+        Ok(())
+    }
+
+    #[stest::test]
+    fn test_preheat_caches() -> Result<()> {
+        let config = Arc::new(NodeConfig::random_for_test());
+        let chain = test_helper::chain::gen_blockchain_with_blocks_for_test(10, config.net())?;
+        let startup_info = StartupInfo::new(chain.current_header().id());
+        let inner = ChainReaderServiceInner::new(
+            config.clone(),
+            startup_info.clone(),
+            chain.get_storage(),
+            None,
+        )?;
+
+        assert_eq!(
+            inner.preheat_caches(0, MAX_PREHEAT_BUDGET)?,
+            PreheatReport::default()
+        );
+
+        let report = inner.preheat_caches(5, MAX_PREHEAT_BUDGET)?;
+        assert!(report.block_accumulator_nodes > 0);
+        assert!(report.txn_accumulator_nodes > 0);
+        assert_eq!(report.block_headers, 5);
+        assert_eq!(report.block_infos, 5);
+        assert!(!report.budget_exhausted);
+
+        // the oldest leaves, which no block read touched, are in the warmed index caches too.
+        for accumulator in [
+            inner.main.get_block_accumulator(),
+            inner.main.get_txn_accumulator(),
+        ] {
+            let stats = accumulator.cache_stats();
+            accumulator.get_leaf(0)?;
+            let warmed = accumulator.cache_stats();
+            assert!(warmed.hits > stats.hits);
+            assert_eq!(warmed.misses, stats.misses);
+        }
+
+        // the blocks are not read once the accumulators used up the budget.
+        let inner = ChainReaderServiceInner::new(config, startup_info, chain.get_storage(), None)?;
+        let report = inner.preheat_caches(5, 1)?;
+        assert_eq!(report.block_accumulator_nodes, 1);
+        assert_eq!(report.txn_accumulator_nodes, 0);
+        assert_eq!(report.block_headers, 0);
+        assert!(report.budget_exhausted);
+        Ok(())
+    }
+
+    #[stest::test]
+    fn test_estimated_time_remaining() -> Result<()> {
+        let config = Arc::new(NodeConfig::random_for_test());
+        let (storage, chain_info, _) = test_helper::Genesis::init_storage_for_test(config.net())?;
+        let mut inner = ChainReaderServiceInner::new(
+            config,
+            StartupInfo::new(chain_info.head().id()),
+            storage,
+            None,
+        )?;
+        // the head is the genesis block, and the target is unknown.
+        assert_eq!(inner.estimated_time_remaining()?, None);
+
+        let target = SyncState::Synchronizing {
+            target: BlockIdAndNumber::new(HashValue::random(), 1000),
+            total_difficulty: U256::zero(),
+        };
+        inner.update_sync_target(&target);
+        // the throughput is unknown.
+        assert_eq!(inner.estimated_time_remaining()?, None);
+
+        // 100 blocks in 10 seconds, 10 blocks per second for the gap of 1000 blocks.
+        let start = Instant::now();
+        inner.throughput.record(start, 0);
+        inner
+            .throughput
+            .record(start + Duration::from_secs(10), 100);
+        assert_eq!(
+            inner.estimated_time_remaining()?,
+            Some(Duration::from_secs(100))
+        );
+
+        // only the recent samples count, the old slow ones slide out of the window.
+        let mut window = ThroughputWindow::new(2);
+        window.record(start, 0);
+        window.record(start + Duration::from_secs(100), 10);
+        window.record(start + Duration::from_secs(110), 110);
+        assert_eq!(window.len(), 2);
+        assert_eq!(window.rate(), Some(10f64));
+
+        inner.update_sync_target(&SyncState::Synchronized);
+        assert_eq!(inner.estimated_time_remaining()?, None);
+        assert!(inner.throughput.is_empty());
+        Ok(())
+    }
 }
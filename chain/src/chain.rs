@@ -10,7 +10,8 @@ use starcoin_accumulator::{
 };
 use starcoin_chain_api::{
     verify_block, ChainReader, ChainWriter, ConnectBlockError, EventWithProof, ExcludedTxns,
-    ExecutedBlock, MintedUncleNumber, TransactionInfoWithProof, VerifiedBlock, VerifyBlockField,
+    ExecutedBlock, MintedUncleNumber, ReorgStatus, TransactionInfoWithProof, VerifiedBlock,
+    VerifyBlockField,
 };
 use starcoin_consensus::Consensus;
 use starcoin_crypto::hash::PlainCryptoHash;
@@ -162,6 +163,58 @@ impl BlockChain {
         self.block_accumulator.get_info()
     }
 
+    /// Check whether the block at `known_number` of the chain whose block accumulator root is
+    /// `known_root` is on this chain. If not, find the common ancestor by comparing the block ids
+    /// of the two accumulators downward, the nodes of the forked accumulator must be in storage.
+    pub fn detect_reorg(
+        &self,
+        known_number: BlockNumber,
+        known_root: HashValue,
+    ) -> Result<ReorgStatus> {
+        let head_number = self.current_header().number();
+        if known_number <= head_number {
+            let block_info = self
+                .get_block_info_by_number(known_number)?
+                .ok_or_else(|| format_err!("Can not find block info by number {}", known_number))?;
+            if *block_info
+                .get_block_accumulator_info()
+                .get_accumulator_root()
+                == known_root
+            {
+                return Ok(ReorgStatus::NoReorg);
+            }
+        }
+        let known_accumulator = self.block_accumulator.fork(Some(AccumulatorInfo::new(
+            known_root,
+            vec![],
+            known_number.saturating_add(1),
+            0,
+        )));
+        let same_leaf = |number: BlockNumber| -> Result<bool> {
+            Ok(known_accumulator.get_leaf(number)? == self.block_accumulator.get_leaf(number)?)
+        };
+        if !same_leaf(0)? {
+            bail!(
+                "Can not find common ancestor with block accumulator root {}",
+                known_root
+            );
+        }
+        // the leaves are the same up to the common ancestor and differ after it, so binary search
+        // the last same leaf.
+        let (mut low, mut high) = (0, min(known_number, head_number));
+        while low < high {
+            let mid = low + (high - low + 1) / 2;
+            if same_leaf(mid)? {
+                low = mid;
+            } else {
+                high = mid - 1;
+            }
+        }
+        Ok(ReorgStatus::Reorged {
+            common_ancestor: low,
+        })
+    }
+
     pub fn consensus(&self) -> ConsensusStrategy {
         self.epoch.strategy()
     }
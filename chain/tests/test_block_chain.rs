@@ -6,6 +6,7 @@ use starcoin_account_api::AccountInfo;
 use starcoin_accumulator::Accumulator;
 use starcoin_chain::BlockChain;
 use starcoin_chain::{ChainReader, ChainWriter};
+use starcoin_chain_api::ReorgStatus;
 use starcoin_chain_mock::MockChain;
 use starcoin_config::NodeConfig;
 use starcoin_config::{BuiltinNetworkID, ChainNetwork};
@@ -185,6 +186,31 @@ fn test_find_ancestor_fork() -> Result<()> {
     Ok(())
 }
 
+#[stest::test]
+fn test_detect_reorg() -> Result<()> {
+    let mut mock_chain = MockChain::new(ChainNetwork::new_test())?;
+    mock_chain.produce_and_apply_times(3)?;
+    let mut mock_chain2 = mock_chain.fork(None)?;
+    mock_chain.produce_and_apply_times(2)?;
+    mock_chain2.produce_and_apply_times(3)?;
+
+    let known = mock_chain.head().current_header().number();
+    let known_root = *mock_chain
+        .head()
+        .current_block_accumulator_info()
+        .get_accumulator_root();
+    assert_eq!(
+        mock_chain.head().detect_reorg(known, known_root)?,
+        ReorgStatus::NoReorg
+    );
+    // the block at number 5 of mock_chain is not on mock_chain2.
+    assert_eq!(
+        mock_chain2.head().detect_reorg(known, known_root)?,
+        ReorgStatus::Reorged { common_ancestor: 3 }
+    );
+    Ok(())
+}
+
 fn gen_uncle() -> (MockChain, BlockChain, BlockHeader) {
     let mut mock_chain = MockChain::new(ChainNetwork::new_test()).unwrap();
     let mut times = 10;
@@ -2,380 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use anyhow::Result;
-use starcoin_chain::BlockChain;
-use starcoin_chain::ChainWriter;
-use starcoin_config::{ChainNetwork, NodeConfig};
-use starcoin_consensus::Consensus;
-use starcoin_transaction_builder::{encode_create_account_script_function, DEFAULT_MAX_GAS_AMOUNT};
+use starcoin_config::NodeConfig;
 use starcoin_types::account::Account;
-use starcoin_types::account_address::AccountAddress;
-use starcoin_types::account_config::association_address;
-use starcoin_types::account_config::stc_type_tag;
-use starcoin_types::block::Block;
-use starcoin_types::genesis_config::ChainId;
-use starcoin_types::transaction::{ScriptFunction, SignedUserTransaction, TransactionPayload};
-use starcoin_vm_types::account_config::core_code_address;
-use starcoin_vm_types::identifier::Identifier;
-use starcoin_vm_types::language_storage::ModuleId;
-use starcoin_vm_types::language_storage::TypeTag;
 use starcoin_vm_types::on_chain_config::consensus_config_type_tag;
-use starcoin_vm_types::transaction::RawUserTransaction;
 use std::sync::Arc;
-use test_helper::dao::{
-    min_action_delay, proposal_state, quorum_vote, voting_delay, voting_period, ACTIVE, AGREED,
-    EXECUTABLE, EXTRACTED, PENDING, QUEUED,
-};
-use test_helper::executor::{get_balance, get_sequence_number};
-
-pub fn create_new_block(
-    chain: &BlockChain,
-    account: &Account,
-    txns: Vec<SignedUserTransaction>,
-) -> Result<Block> {
-    let (template, _) =
-        chain.create_block_template(*account.address(), None, txns, vec![], None)?;
-    chain
-        .consensus()
-        .create_block(template, chain.time_service().as_ref())
-}
-
-pub fn build_transaction(
-    user_address: AccountAddress,
-    seq_number: u64,
-    payload: TransactionPayload,
-    expire_time: u64,
-) -> RawUserTransaction {
-    RawUserTransaction::new_with_default_gas_token(
-        user_address,
-        seq_number,
-        payload,
-        DEFAULT_MAX_GAS_AMOUNT,
-        1,
-        expire_time + 60 * 60,
-        ChainId::test(),
-    )
-}
-
-fn create_user_txn(
-    address: AccountAddress,
-    seq_number: u64,
-    net: &ChainNetwork,
-    alice: &Account,
-    pre_mint_amount: u128,
-    expire_time: u64,
-) -> Result<Vec<SignedUserTransaction>> {
-    let script_function = encode_create_account_script_function(
-        net.stdlib_version(),
-        stc_type_tag(),
-        alice.address(),
-        alice.auth_key(),
-        pre_mint_amount / 4,
-    );
-    let txn = net
-        .genesis_config()
-        .sign_with_association(build_transaction(
-            address,
-            seq_number,
-            TransactionPayload::ScriptFunction(script_function),
-            expire_time + 60 * 60,
-        ))?;
-    Ok(vec![txn])
-}
-
-fn build_create_vote_txn(
-    alice: &Account,
-    seq_number: u64,
-    vote_script_function: ScriptFunction,
-    expire_time: u64,
-) -> SignedUserTransaction {
-    alice.sign_txn(build_transaction(
-        *alice.address(),
-        seq_number,
-        TransactionPayload::ScriptFunction(vote_script_function),
-        expire_time,
-    ))
-}
-
-fn build_cast_vote_txn(
-    seq_number: u64,
-    alice: &Account,
-    action_type_tag: TypeTag,
-    voting_power: u128,
-    expire_time: u64,
-) -> SignedUserTransaction {
-    let proposer_id: u64 = 0;
-    println!("alice voting power: {}", voting_power);
-    let vote_script_function = ScriptFunction::new(
-        ModuleId::new(
-            core_code_address(),
-            Identifier::new("DaoVoteScripts").unwrap(),
-        ),
-        Identifier::new("cast_vote").unwrap(),
-        vec![stc_type_tag(), action_type_tag],
-        vec![
-            bcs_ext::to_bytes(alice.address()).unwrap(),
-            bcs_ext::to_bytes(&proposer_id).unwrap(),
-            bcs_ext::to_bytes(&true).unwrap(),
-            bcs_ext::to_bytes(&(voting_power / 2)).unwrap(),
-        ],
-    );
-    alice.sign_txn(build_transaction(
-        *alice.address(),
-        seq_number,
-        TransactionPayload::ScriptFunction(vote_script_function),
-        expire_time,
-    ))
-}
-
-fn build_queue_txn(
-    seq_number: u64,
-    alice: &Account,
-    _net: &ChainNetwork,
-    action_type_tag: TypeTag,
-    expire_time: u64,
-) -> SignedUserTransaction {
-    let script_function = ScriptFunction::new(
-        ModuleId::new(core_code_address(), Identifier::new("Dao").unwrap()),
-        Identifier::new("queue_proposal_action").unwrap(),
-        vec![stc_type_tag(), action_type_tag],
-        vec![
-            bcs_ext::to_bytes(alice.address()).unwrap(),
-            bcs_ext::to_bytes(&0u64).unwrap(),
-        ],
-    );
-    alice.sign_txn(build_transaction(
-        *alice.address(),
-        seq_number,
-        TransactionPayload::ScriptFunction(script_function),
-        expire_time,
-    ))
-}
-
-fn build_execute_txn(
-    seq_number: u64,
-    alice: &Account,
-    execute_script_function: ScriptFunction,
-    expire_time: u64,
-) -> SignedUserTransaction {
-    alice.sign_txn(build_transaction(
-        *alice.address(),
-        seq_number,
-        TransactionPayload::ScriptFunction(execute_script_function),
-        expire_time,
-    ))
-}
-
-pub fn modify_on_chain_config_by_dao_block(
-    alice: Account,
-    mut chain: BlockChain,
-    net: &ChainNetwork,
-    vote_script: ScriptFunction,
-    action_type_tag: TypeTag,
-    execute_script: ScriptFunction,
-) -> Result<BlockChain> {
-    let pre_mint_amount = net.genesis_config().pre_mine_amount;
-    let one_day: u64 = 60 * 60 * 24 * 1000;
-    let address = association_address();
-
-    // Block 1
-    let block_number = 1;
-    let block_timestamp = net.time_service().now_millis() + one_day * block_number;
-    let chain_state = chain.chain_state();
-    let seq = get_sequence_number(address, chain_state);
-    {
-        chain.time_service().adjust(block_timestamp);
-
-        let (template, _) = chain.create_block_template(
-            address,
-            None,
-            create_user_txn(
-                address,
-                seq,
-                net,
-                &alice,
-                pre_mint_amount,
-                block_timestamp / 1000,
-            )?,
-            vec![],
-            None,
-        )?;
-        let block1 = chain
-            .consensus()
-            .create_block(template, chain.time_service().as_ref())?;
-
-        chain.apply(block1)?;
-    }
-
-    // block 2
-    let block_number = 2;
-    let block_timestamp = net.time_service().now_millis() + one_day * block_number;
-    let chain_state = chain.chain_state();
-    let alice_seq = get_sequence_number(*alice.address(), chain_state);
-    {
-        chain.time_service().adjust(block_timestamp);
-        let block2 = create_new_block(
-            &chain,
-            &alice,
-            vec![build_create_vote_txn(
-                &alice,
-                alice_seq,
-                vote_script,
-                block_timestamp / 1000,
-            )],
-        )?;
-        chain.apply(block2)?;
-
-        let chain_state = chain.chain_state();
-        let state = proposal_state(
-            chain_state,
-            stc_type_tag(),
-            action_type_tag.clone(),
-            *alice.address(),
-            0,
-        );
-        assert_eq!(state, PENDING);
-    }
-
-    // block 3
-    //voting delay
-    let chain_state = chain.chain_state();
-    let voting_power = get_balance(*alice.address(), chain_state);
-    let alice_seq = get_sequence_number(*alice.address(), chain_state);
-    let block_timestamp = block_timestamp + voting_delay(chain_state, stc_type_tag()) + 10000;
-    {
-        chain.time_service().adjust(block_timestamp);
-        let block3 = create_new_block(
-            &chain,
-            &alice,
-            vec![build_cast_vote_txn(
-                alice_seq,
-                &alice,
-                action_type_tag.clone(),
-                voting_power,
-                block_timestamp / 1000,
-            )],
-        )?;
-        chain.apply(block3)?;
-    }
-    // block 4
-    let chain_state = chain.chain_state();
-    let block_timestamp = block_timestamp + voting_period(chain_state, stc_type_tag()) - 10000;
-    {
-        chain.time_service().adjust(block_timestamp);
-        let block4 = create_new_block(&chain, &alice, vec![])?;
-        chain.apply(block4)?;
-        let chain_state = chain.chain_state();
-        let quorum = quorum_vote(chain_state, stc_type_tag());
-        println!("quorum: {}", quorum);
-
-        let state = proposal_state(
-            chain_state,
-            stc_type_tag(),
-            action_type_tag.clone(),
-            *alice.address(),
-            0,
-        );
-        assert_eq!(state, ACTIVE);
-    }
-
-    // block 5
-    let block_timestamp = block_timestamp + 20 * 1000;
-    {
-        chain.time_service().adjust(block_timestamp);
-        chain.apply(create_new_block(&chain, &alice, vec![])?)?;
-        let chain_state = chain.chain_state();
-        let state = proposal_state(
-            chain_state,
-            stc_type_tag(),
-            action_type_tag.clone(),
-            *alice.address(),
-            0,
-        );
-        assert_eq!(state, AGREED, "expect AGREED state, but got {}", state);
-    }
-
-    // block 6
-    let chain_state = chain.chain_state();
-    let alice_seq = get_sequence_number(*alice.address(), chain_state);
-    let block_timestamp = block_timestamp + 20 * 1000;
-    {
-        chain.time_service().adjust(block_timestamp);
-        let block6 = create_new_block(
-            &chain,
-            &alice,
-            vec![build_queue_txn(
-                alice_seq,
-                &alice,
-                net,
-                action_type_tag.clone(),
-                block_timestamp / 1000,
-            )],
-        )?;
-        chain.apply(block6)?;
-        let chain_state = chain.chain_state();
-        let state = proposal_state(
-            chain_state,
-            stc_type_tag(),
-            action_type_tag.clone(),
-            *alice.address(),
-            0,
-        );
-        assert_eq!(state, QUEUED);
-    }
-
-    // block 7
-    let chain_state = chain.chain_state();
-    let block_timestamp = block_timestamp + min_action_delay(chain_state, stc_type_tag());
-    {
-        chain.time_service().adjust(block_timestamp);
-        chain.apply(create_new_block(&chain, &alice, vec![])?)?;
-        let chain_state = chain.chain_state();
-        let state = proposal_state(
-            chain_state,
-            stc_type_tag(),
-            action_type_tag.clone(),
-            *alice.address(),
-            0,
-        );
-        assert_eq!(state, EXECUTABLE);
-    }
-
-    let chain_state = chain.chain_state();
-    let alice_seq = get_sequence_number(*alice.address(), chain_state);
-    {
-        let block8 = create_new_block(
-            &chain,
-            &alice,
-            vec![build_execute_txn(
-                alice_seq,
-                &alice,
-                execute_script,
-                block_timestamp / 1000,
-            )],
-        )?;
-        chain.apply(block8)?;
-    }
-
-    // block 9
-    let block_timestamp = block_timestamp + 1000;
-    let _chain_state = chain.chain_state();
-    {
-        chain.time_service().adjust(block_timestamp);
-        chain.apply(create_new_block(&chain, &alice, vec![])?)?;
-        let chain_state = chain.chain_state();
-        let state = proposal_state(
-            chain_state,
-            stc_type_tag(),
-            action_type_tag,
-            *alice.address(),
-            0,
-        );
-        assert_eq!(state, EXTRACTED);
-    }
-
-    // return chain state for verify
-    Ok(chain)
-}
 
 #[stest::test(timeout = 120)]
 fn test_modify_on_chain_config_consensus_by_dao() -> Result<()> {
@@ -2,11 +2,25 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    node_index::NodeIndex, tree_store::mock::MockAccumulatorStore, Accumulator, AccumulatorNode,
+    accumulator_info::AccumulatorInfo,
+    data_accumulator::DataAccumulator,
+    inmemory::InMemoryAccumulator,
+    metrics::AccumulatorMetrics,
+    node_index::NodeIndex,
+    proof::{
+        verify_leaf, AccumulatorConsistencyProof, AccumulatorRangeProof, ProofCache,
+        SelfContainedProof,
+    },
+    startup_integrity_check,
+    tree_store::{
+        batching::BatchingWriter, copy_store, mock::MockAccumulatorStore, CompactionStats,
+    },
+    Accumulator, AccumulatorCacheConfig, AccumulatorCacheStats, AccumulatorNode,
     AccumulatorTreeStore, LeafCount, MerkleAccumulator,
 };
 use starcoin_crypto::{hash::ACCUMULATOR_PLACEHOLDER_HASH, HashValue};
-use std::time::SystemTime;
+use starcoin_metrics::Registry;
+use std::time::{Duration, SystemTime};
 use std::{collections::HashMap, sync::Arc};
 
 #[test]
@@ -327,6 +341,196 @@ fn test_get_leaves_overflow() {
 #[test]
 fn test_get_frozen_subtrees() {}
 
+#[test]
+fn test_startup_integrity_check() {
+    let leaves = create_leaves(0..1000);
+    let mock_store = Arc::new(MockAccumulatorStore::new());
+    let accumulator = MerkleAccumulator::new_empty(mock_store.clone());
+    let root_hash = accumulator.append(&leaves).unwrap();
+    accumulator.flush().unwrap();
+
+    startup_integrity_check(mock_store.clone(), root_hash, leaves.len() as u64).unwrap();
+    assert!(startup_integrity_check(mock_store.clone(), root_hash, 999).is_err());
+    assert!(
+        startup_integrity_check(mock_store.clone(), HashValue::random(), leaves.len() as u64)
+            .is_err()
+    );
+    startup_integrity_check(mock_store, *ACCUMULATOR_PLACEHOLDER_HASH, 0).unwrap();
+}
+
+#[test]
+fn test_startup_integrity_check_tampered_store() {
+    let leaves = create_leaves(0..1000);
+    let mock_store = Arc::new(MockAccumulatorStore::new());
+    let accumulator = MerkleAccumulator::new_empty(mock_store.clone());
+    let root_hash = accumulator.append(&leaves).unwrap();
+    accumulator.flush().unwrap();
+
+    // drop the first frozen subtree root, the nodes below it can not be reached any more.
+    let tampered_store = Arc::new(mock_store.copy_from());
+    let frozen_root = accumulator.get_frozen_subtree_roots()[0];
+    tampered_store.delete_nodes(vec![frozen_root]).unwrap();
+    assert!(startup_integrity_check(tampered_store, root_hash, leaves.len() as u64).is_err());
+}
+
+#[test]
+fn test_proof_cache() {
+    let leaves = create_leaves(0..100);
+    let accumulator = MerkleAccumulator::new_empty(Arc::new(MockAccumulatorStore::new()));
+    let root_hash = accumulator.append(&leaves).unwrap();
+    accumulator.flush().unwrap();
+    let cache = ProofCache::new(16);
+
+    let proof = cache.get_proof(&accumulator, 10).unwrap().unwrap();
+    assert_eq!(cache.hits(), 0);
+    assert_eq!(cache.misses(), 1);
+    let cached_proof = cache.get_proof(&accumulator, 10).unwrap().unwrap();
+    assert_eq!(cache.hits(), 1);
+    assert_eq!(cache.misses(), 1);
+    assert_eq!(proof, cached_proof);
+    cached_proof.verify(root_hash, leaves[10], 10).unwrap();
+
+    // the cached proofs are invalid after root changed.
+    let new_root_hash = accumulator.append(&create_leaves(100..101)).unwrap();
+    let new_proof = cache.get_proof(&accumulator, 10).unwrap().unwrap();
+    assert_eq!(cache.hits(), 1);
+    assert_eq!(cache.misses(), 2);
+    new_proof.verify(new_root_hash, leaves[10], 10).unwrap();
+
+    // a zero capacity disables the cache.
+    let cache = ProofCache::new(0);
+    assert_eq!(cache.capacity(), 0);
+    let proof = cache.get_proof(&accumulator, 10).unwrap().unwrap();
+    proof.verify(new_root_hash, leaves[10], 10).unwrap();
+    cache.get_proof(&accumulator, 10).unwrap().unwrap();
+    cache.get_proof(&accumulator, 11).unwrap().unwrap();
+    assert_eq!(cache.hits(), 0);
+    assert_eq!(cache.misses(), 3);
+}
+
+#[test]
+fn test_self_contained_proof() {
+    let leaves = create_leaves(0..50);
+    let accumulator = MerkleAccumulator::new_empty(Arc::new(MockAccumulatorStore::new()));
+    let root_hash = accumulator.append(&leaves).unwrap();
+    let num_leaves = accumulator.num_leaves();
+
+    let proof = accumulator.get_proof(20).unwrap().unwrap();
+    let self_contained = SelfContainedProof::new(num_leaves, 20, leaves[20], proof);
+    self_contained.verify(root_hash).unwrap();
+
+    let mut out_of_range = self_contained.clone();
+    out_of_range.leaf_index = num_leaves;
+    assert!(out_of_range.verify(root_hash).is_err());
+
+    // the leaf count does not match the proof depth.
+    let mut wrong_count = self_contained.clone();
+    wrong_count.num_leaves = 1000;
+    assert!(wrong_count.verify(root_hash).is_err());
+
+    let mut wrong_leaf = self_contained;
+    wrong_leaf.leaf = leaves[21];
+    assert!(wrong_leaf.verify(root_hash).is_err());
+}
+
+#[test]
+fn test_verify_leaf_stateless() {
+    let leaves = create_leaves(0..37);
+    let accumulator = MerkleAccumulator::new_empty(Arc::new(MockAccumulatorStore::new()));
+    let root_hash = accumulator.append(&leaves).unwrap();
+    let num_leaves = accumulator.num_leaves();
+    let siblings = accumulator.get_proof(30).unwrap().unwrap().siblings;
+    drop(accumulator);
+
+    verify_leaf(root_hash, num_leaves, 30, leaves[30], &siblings).unwrap();
+    assert!(verify_leaf(root_hash, num_leaves, 30, leaves[29], &siblings).is_err());
+}
+
+#[test]
+fn test_data_accumulator() {
+    let store = Arc::new(MockAccumulatorStore::new());
+    let accumulator = DataAccumulator::new(MerkleAccumulator::new_empty(store.clone()), store);
+    let leaves: Vec<(HashValue, Vec<u8>)> = (0..10u8)
+        .map(|i| (HashValue::sha3_256_of(&[i]), vec![i]))
+        .collect();
+    let root_hash = accumulator.append(&leaves).unwrap();
+    assert_eq!(root_hash, accumulator.accumulator().root_hash());
+
+    for (i, (_, data)) in leaves.iter().enumerate() {
+        assert_eq!(
+            accumulator.get_leaf_data(i as u64).unwrap().as_ref(),
+            Some(data)
+        );
+    }
+    assert!(accumulator.get_leaf_data(10).unwrap().is_none());
+}
+
+#[test]
+fn test_batching_writer() {
+    let writer = BatchingWriter::new(MockAccumulatorStore::new(), 4, Duration::from_secs(3600));
+    let nodes = create_leaf_nodes(0..6);
+
+    writer.save_nodes(nodes[..3].to_vec()).unwrap();
+    assert_eq!(writer.buffered(), 3);
+    for node in &nodes[..3] {
+        assert!(writer.inner().get_node(node.hash()).is_err());
+        assert_eq!(writer.get_node(node.hash()).unwrap().as_ref(), Some(node));
+    }
+    writer.flush().unwrap();
+    assert_eq!(writer.buffered(), 0);
+    for node in &nodes[..3] {
+        assert!(writer.inner().get_node(node.hash()).unwrap().is_some());
+    }
+
+    // the batch is written once it reaches the max batch size.
+    for node in &nodes[3..] {
+        writer.save_node(node.clone()).unwrap();
+    }
+    assert_eq!(writer.buffered(), 3);
+    writer.save_node(nodes[0].clone()).unwrap();
+    assert_eq!(writer.buffered(), 0);
+    for node in &nodes[3..] {
+        assert!(writer.inner().get_node(node.hash()).unwrap().is_some());
+    }
+}
+
+#[test]
+fn test_batching_writer_multiple_get() {
+    let writer = BatchingWriter::new(MockAccumulatorStore::new(), 10, Duration::from_secs(3600));
+    let nodes = create_leaf_nodes(0..3);
+    writer.save_node(nodes[0].clone()).unwrap();
+    writer.flush().unwrap();
+    writer.save_node(nodes[1].clone()).unwrap();
+    assert_eq!(writer.buffered(), 1);
+
+    let missing = HashValue::random();
+    let result = writer
+        .multiple_get(vec![
+            nodes[1].hash(),
+            missing,
+            nodes[0].hash(),
+            nodes[2].hash(),
+        ])
+        .unwrap();
+    assert_eq!(
+        result,
+        vec![Some(nodes[1].clone()), None, Some(nodes[0].clone()), None]
+    );
+}
+
+#[test]
+fn test_compact_mock_store() {
+    let store = Arc::new(MockAccumulatorStore::new());
+    let accumulator = MerkleAccumulator::new_empty(store.clone());
+    accumulator.append(&create_leaves(0..8)).unwrap();
+    accumulator.flush().unwrap();
+    let leaf = accumulator.get_leaf(0).unwrap().unwrap();
+    let leaf_node = AccumulatorNode::new_leaf(NodeIndex::from_leaf_index(0), leaf);
+    store.delete_nodes(vec![leaf_node.hash()]).unwrap();
+    assert!(store.get_node(leaf_node.hash()).is_err());
+    assert_eq!(store.compact().unwrap(), CompactionStats::default());
+}
+
 fn proof_verify(
     accumulator: &MerkleAccumulator,
     root_hash: HashValue,
@@ -351,6 +555,15 @@ fn create_leaves(nums: std::ops::Range<usize>) -> Vec<HashValue> {
         .collect()
 }
 
+// Creates the leaf nodes of `create_leaves(nums)`, indexed from the leaf 0.
+fn create_leaf_nodes(nums: std::ops::Range<usize>) -> Vec<AccumulatorNode> {
+    create_leaves(nums)
+        .into_iter()
+        .enumerate()
+        .map(|(i, leaf)| AccumulatorNode::new_leaf(NodeIndex::from_leaf_index(i as u64), leaf))
+        .collect()
+}
+
 // Computes the root hash of an accumulator with given leaves.
 fn compute_root_hash_naive(leaves: &[HashValue]) -> HashValue {
     let position_to_hash = compute_hashes_for_all_positions(leaves);
@@ -422,3 +635,549 @@ fn compute_parent_hash(
         AccumulatorNode::new_internal(node_index, left_hash, right_hash).hash()
     }
 }
+
+#[test]
+fn test_mock_store_multiple_get() {
+    let store = MockAccumulatorStore::new();
+    let nodes = create_leaf_nodes(0..3);
+    store.save_nodes(nodes.clone()).unwrap();
+
+    let absent = HashValue::random();
+    let result = store
+        .multiple_get(vec![
+            nodes[2].hash(),
+            absent,
+            nodes[0].hash(),
+            nodes[1].hash(),
+        ])
+        .unwrap();
+    assert_eq!(result.len(), 4);
+    assert_eq!(
+        result,
+        vec![
+            Some(nodes[2].clone()),
+            None,
+            Some(nodes[0].clone()),
+            Some(nodes[1].clone()),
+        ]
+    );
+}
+
+#[test]
+fn test_index_cache_capacity() {
+    let nodes = create_leaf_nodes(0..3);
+
+    let accumulator = MerkleAccumulator::new_with_cache_config(
+        AccumulatorInfo::default(),
+        Arc::new(MockAccumulatorStore::new()),
+        AccumulatorCacheConfig { index_capacity: 2 },
+    );
+    accumulator.cache_node_indexes(nodes.clone());
+    // the least recently used one is evicted.
+    assert_eq!(accumulator.cached_node_index(nodes[0].index()), None);
+    assert_eq!(
+        accumulator.cached_node_index(nodes[1].index()),
+        Some(nodes[1].hash())
+    );
+    assert_eq!(
+        accumulator.cached_node_index(nodes[2].index()),
+        Some(nodes[2].hash())
+    );
+    assert_eq!(
+        accumulator.fork(None).cached_node_index(nodes[2].index()),
+        None
+    );
+
+    let no_cache = MerkleAccumulator::new_with_cache_config(
+        AccumulatorInfo::default(),
+        Arc::new(MockAccumulatorStore::new()),
+        AccumulatorCacheConfig { index_capacity: 0 },
+    );
+    no_cache.cache_node_indexes(nodes.clone());
+    assert_eq!(no_cache.cached_node_index(nodes[0].index()), None);
+}
+
+#[test]
+fn test_index_cache_metrics() {
+    let metrics = AccumulatorMetrics::register(&Registry::new()).unwrap();
+    let counter = |event: &str| metrics.index_cache_total.with_label_values(&[event]).get();
+
+    let leaves = create_leaves(0..4);
+    let accumulator = MerkleAccumulator::new_empty(Arc::new(
+        MockAccumulatorStore::new_with_metrics(Some(metrics.clone())),
+    ));
+    accumulator.append(&leaves).unwrap();
+    accumulator.get_leaf(3).unwrap().unwrap();
+    let stats = accumulator.cache_stats();
+    assert!(stats.insertions > 0);
+    assert_eq!(counter("hit"), stats.hits);
+    assert_eq!(counter("miss"), stats.misses);
+    assert_eq!(counter("insertion"), stats.insertions);
+    assert_eq!(counter("eviction"), stats.evictions);
+
+    // an accumulator on a store without metrics does not report to them.
+    let other = MerkleAccumulator::new_empty(Arc::new(MockAccumulatorStore::new()));
+    other.append(&leaves).unwrap();
+    assert_eq!(counter("insertion"), stats.insertions);
+}
+
+#[test]
+fn test_index_cache_stats() {
+    let nodes = create_leaf_nodes(0..3);
+    let info = AccumulatorInfo::new(HashValue::random(), vec![], 3, 5);
+    let accumulator = MerkleAccumulator::new_with_cache_config(
+        info,
+        Arc::new(MockAccumulatorStore::new()),
+        AccumulatorCacheConfig { index_capacity: 2 },
+    );
+    accumulator.cache_node_indexes(nodes.clone());
+    assert_eq!(
+        accumulator.cache_stats(),
+        AccumulatorCacheStats {
+            hits: 0,
+            misses: 0,
+            insertions: 3,
+            evictions: 1,
+        }
+    );
+
+    let leaf_2 = accumulator
+        .get_node_by_position(nodes[2].index().to_inorder_index())
+        .unwrap();
+    assert_eq!(leaf_2, Some(nodes[2].hash()));
+    assert_eq!(accumulator.cache_stats().hits, 1);
+    assert_eq!(accumulator.cache_stats().misses, 0);
+
+    // the evicted leaf misses, and so do its ancestors, then the lookup falls back to the store.
+    assert!(accumulator
+        .get_node_by_position(nodes[0].index().to_inorder_index())
+        .is_err());
+    assert_eq!(accumulator.cache_stats().hits, 1);
+    assert_eq!(accumulator.cache_stats().misses, 4);
+}
+
+#[test]
+fn test_delete_nodes_purge_index_cache() {
+    let leaf = HashValue::random();
+    // two leaves with the same value share a node hash.
+    let nodes: Vec<AccumulatorNode> = (0..2)
+        .map(|i| AccumulatorNode::new_leaf(NodeIndex::from_leaf_index(i), leaf))
+        .collect();
+    let other = AccumulatorNode::new_leaf(NodeIndex::from_leaf_index(2), HashValue::random());
+    let store = Arc::new(MockAccumulatorStore::new());
+    let accumulator = MerkleAccumulator::new_with_info(
+        AccumulatorInfo::new(HashValue::random(), vec![], 3, 5),
+        store.clone(),
+    );
+    let mut all_nodes = nodes.clone();
+    all_nodes.push(other.clone());
+    store.save_nodes(all_nodes.clone()).unwrap();
+    accumulator.cache_node_indexes(all_nodes);
+    for node in &nodes {
+        assert_eq!(accumulator.cached_node_index(node.index()), Some(leaf));
+    }
+
+    accumulator.delete_nodes(vec![leaf]).unwrap();
+    for node in &nodes {
+        assert_eq!(accumulator.cached_node_index(node.index()), None);
+        let position = node.index().to_inorder_index();
+        assert_ne!(
+            accumulator.get_node_by_position(position).ok().flatten(),
+            Some(leaf)
+        );
+    }
+    assert_eq!(
+        accumulator.cached_node_index(other.index()),
+        Some(other.hash())
+    );
+    assert!(store.get_node(leaf).is_err());
+}
+
+#[test]
+fn test_consistency_proof() {
+    let leaves = create_leaves(0..70);
+    let accumulator = MerkleAccumulator::new_empty(Arc::new(MockAccumulatorStore::new()));
+    accumulator.append(&leaves).unwrap();
+    let root_of = |size: usize| InMemoryAccumulator::from_leaves(&leaves[..size]).root_hash();
+
+    for (from_size, to_size) in [
+        (0, 1),
+        (1, 2),
+        (2, 4),
+        (3, 8),
+        (4, 17),
+        (8, 64),
+        (63, 64),
+        (64, 65),
+        (5, 70),
+        (0, 70),
+        (33, 33),
+    ] {
+        let proof = accumulator
+            .get_consistency_proof(from_size, to_size)
+            .unwrap();
+        proof
+            .verify(
+                root_of(from_size as usize),
+                from_size,
+                root_of(to_size as usize),
+                to_size,
+            )
+            .unwrap();
+        if from_size == to_size {
+            assert_eq!(proof, AccumulatorConsistencyProof::default());
+        } else {
+            // the new root is not an extension of a different old root.
+            assert!(proof
+                .verify(
+                    root_of(to_size as usize),
+                    from_size,
+                    root_of(to_size as usize),
+                    to_size,
+                )
+                .is_err());
+        }
+    }
+
+    assert!(accumulator.get_consistency_proof(8, 4).is_err());
+    assert!(accumulator.get_consistency_proof(4, 71).is_err());
+}
+
+#[test]
+fn test_range_proof() {
+    let leaves = create_leaves(0..37);
+    let accumulator = MerkleAccumulator::new_empty(Arc::new(MockAccumulatorStore::new()));
+    let root_hash = accumulator.append(&leaves).unwrap();
+    let num_leaves = accumulator.num_leaves();
+
+    for (start, end) in [(0, 1), (20, 21), (36, 37), (3, 17), (0, 37), (8, 16)] {
+        let proof = accumulator.get_range_proof(num_leaves, start, end).unwrap();
+        let range = &leaves[start as usize..end as usize];
+        proof.verify(root_hash, start, range).unwrap();
+    }
+
+    // the whole tree needs no siblings but the placeholders on the right.
+    let full_proof = accumulator
+        .get_range_proof(num_leaves, 0, num_leaves)
+        .unwrap();
+    assert!(full_proof.left_siblings.is_empty());
+
+    let mut wrong_leaves = leaves[3..17].to_vec();
+    wrong_leaves[5] = HashValue::random();
+    let proof = accumulator.get_range_proof(num_leaves, 3, 17).unwrap();
+    assert!(proof.verify(root_hash, 3, &wrong_leaves).is_err());
+
+    assert!(accumulator.get_range_proof(num_leaves, 30, 38).is_err());
+    assert!(accumulator.get_range_proof(num_leaves, 5, 5).is_err());
+    assert!(accumulator.get_range_proof(num_leaves + 1, 0, 1).is_err());
+    assert!(AccumulatorRangeProof::default()
+        .verify(root_hash, 0, &leaves)
+        .is_err());
+}
+
+#[test]
+fn test_export_import_frozen_subtrees() {
+    let leaves = create_leaves(0..21);
+    let accumulator = MerkleAccumulator::new_empty(Arc::new(MockAccumulatorStore::new()));
+    let root_hash = accumulator.append(&leaves).unwrap();
+    let subtrees = accumulator.export_frozen_subtrees().unwrap();
+    assert_eq!(subtrees.len(), 3);
+
+    let imported = MerkleAccumulator::new_empty(Arc::new(MockAccumulatorStore::new()));
+    let mut tampered = subtrees.clone();
+    tampered[1].1 = HashValue::random();
+    assert!(imported
+        .import_frozen_subtrees(21, tampered, root_hash)
+        .is_err());
+    assert!(imported
+        .import_frozen_subtrees(22, subtrees.clone(), root_hash)
+        .is_err());
+    imported
+        .import_frozen_subtrees(21, subtrees, root_hash)
+        .unwrap();
+    assert_eq!(imported.root_hash(), root_hash);
+    assert_eq!(imported.get_info(), accumulator.get_info());
+
+    let new_leaves = create_leaves(21..40);
+    assert_eq!(
+        imported.append(&new_leaves).unwrap(),
+        accumulator.append(&new_leaves).unwrap()
+    );
+    assert_eq!(imported.get_info(), accumulator.get_info());
+}
+
+#[test]
+fn test_prune() {
+    let leaves = create_leaves(0..50);
+    let store = Arc::new(MockAccumulatorStore::new());
+    let accumulator = MerkleAccumulator::new_empty(store.clone());
+    accumulator.append(&leaves).unwrap();
+    accumulator.flush().unwrap();
+    let old_internal = accumulator
+        .get_node_by_position(NodeIndex::from_level_and_pos(2, 0).to_inorder_index())
+        .unwrap()
+        .unwrap();
+
+    assert!(accumulator.prune(51).is_err());
+    // the internal nodes of the subtrees of leaves [0, 16) and [16, 20).
+    assert_eq!(accumulator.prune(20).unwrap(), 15 + 3);
+    assert_eq!(accumulator.prune(20).unwrap(), 0);
+    assert_eq!(store.multiple_get(vec![old_internal]).unwrap(), vec![None]);
+
+    // a reloaded accumulator without caches still proves the retained leaves.
+    let reloaded = MerkleAccumulator::new_with_info(accumulator.get_info(), store.clone());
+    let root_hash = reloaded.root_hash();
+    for leaf_index in 20..50 {
+        let leaf = reloaded.get_leaf(leaf_index).unwrap().unwrap();
+        assert_eq!(leaf, leaves[leaf_index as usize]);
+        let proof = reloaded.get_proof(leaf_index).unwrap().unwrap();
+        proof.verify(root_hash, leaf, leaf_index).unwrap();
+    }
+
+    let new_leaves = create_leaves(50..70);
+    let expected = MerkleAccumulator::new_empty(Arc::new(MockAccumulatorStore::new()));
+    expected.append(&leaves).unwrap();
+    assert_eq!(
+        reloaded.append(&new_leaves).unwrap(),
+        expected.append(&new_leaves).unwrap()
+    );
+}
+
+#[test]
+fn test_copy_store() {
+    let src = Arc::new(MockAccumulatorStore::new());
+    let accumulator = MerkleAccumulator::new_empty(src.clone());
+    let root_hash = accumulator.append(&create_leaves(0..30)).unwrap();
+    accumulator.flush().unwrap();
+    let mut src_nodes: Vec<AccumulatorNode> = src
+        .iter_nodes()
+        .unwrap()
+        .map(|node| node.unwrap())
+        .collect();
+    assert!(!src_nodes.is_empty());
+
+    let dst = Arc::new(MockAccumulatorStore::new());
+    assert!(copy_store(src.as_ref(), dst.as_ref(), 0).is_err());
+    assert_eq!(
+        copy_store(src.as_ref(), dst.as_ref(), 7).unwrap(),
+        src_nodes.len() as u64
+    );
+    let mut dst_nodes: Vec<AccumulatorNode> = dst
+        .iter_nodes()
+        .unwrap()
+        .map(|node| node.unwrap())
+        .collect();
+    src_nodes.sort_by_key(|node| node.hash());
+    dst_nodes.sort_by_key(|node| node.hash());
+    assert_eq!(src_nodes, dst_nodes);
+
+    let copied = MerkleAccumulator::new_with_info(accumulator.get_info(), dst);
+    assert_eq!(copied.root_hash(), root_hash);
+    assert_eq!(
+        copied.get_leaf(29).unwrap(),
+        accumulator.get_leaf(29).unwrap()
+    );
+}
+
+#[test]
+fn test_get_proofs() {
+    let leaves = create_leaves(0..1000);
+    let accumulator = MerkleAccumulator::new_empty(Arc::new(MockAccumulatorStore::new()));
+    let root_hash = accumulator.append(&leaves[..600]).unwrap();
+    accumulator.flush().unwrap();
+    // the later leaves are not flushed yet.
+    let root_hash_1000 = accumulator.append(&leaves[600..]).unwrap();
+    assert_ne!(root_hash, root_hash_1000);
+
+    let leaf_indices: Vec<u64> = (0..1000).step_by(7).chain([999, 0, 512]).collect();
+    let proofs = accumulator.get_proofs(1000, &leaf_indices).unwrap();
+    assert_eq!(proofs.len(), leaf_indices.len());
+    for (leaf_index, proof) in leaf_indices.iter().zip(proofs) {
+        assert_eq!(proof, accumulator.get_proof(*leaf_index).unwrap().unwrap());
+        proof
+            .verify(root_hash_1000, leaves[*leaf_index as usize], *leaf_index)
+            .unwrap();
+    }
+
+    assert!(accumulator.get_proofs(1000, &[1000]).is_err());
+    assert!(accumulator.get_proofs(600, &[0]).is_err());
+    assert!(accumulator.get_proofs(1000, &[]).unwrap().is_empty());
+}
+
+#[test]
+fn test_warm_cache() {
+    let leaves = create_leaves(0..64);
+    let mock_store = Arc::new(MockAccumulatorStore::new());
+    let accumulator = MerkleAccumulator::new_empty(mock_store.clone());
+    accumulator.append(&leaves).unwrap();
+    accumulator.flush().unwrap();
+    let info = accumulator.get_info();
+
+    let root = info.accumulator_root;
+    let accumulator = MerkleAccumulator::new_with_info(info.clone(), mock_store.clone());
+    assert!(accumulator
+        .warm_cache(HashValue::random(), u64::MAX)
+        .is_err());
+    // 64 leaves and 63 internal nodes.
+    assert_eq!(accumulator.warm_cache(root, u64::MAX).unwrap(), 127);
+    assert_eq!(accumulator.cache_stats().insertions, 127);
+    // warm again loads nothing new.
+    assert_eq!(accumulator.warm_cache(root, u64::MAX).unwrap(), 0);
+    for (i, leaf) in leaves.iter().enumerate() {
+        let proof = accumulator.get_proof(i as u64).unwrap().unwrap();
+        proof
+            .verify(info.accumulator_root, *leaf, i as u64)
+            .unwrap();
+    }
+    let stats = accumulator.cache_stats();
+    assert_eq!(stats.misses, 0);
+    assert!(stats.hits > 0);
+
+    let accumulator = MerkleAccumulator::new_with_info(info.clone(), mock_store.clone());
+    assert_eq!(accumulator.warm_cache(root, 5).unwrap(), 5);
+
+    let accumulator = MerkleAccumulator::new_with_cache_config(
+        info,
+        mock_store,
+        AccumulatorCacheConfig { index_capacity: 10 },
+    );
+    assert_eq!(accumulator.warm_cache(root, u64::MAX).unwrap(), 10);
+    assert_eq!(accumulator.cache_stats().evictions, 0);
+    // the most recently appended nodes are loaded before the older ones.
+    let root_index = NodeIndex::root_from_leaf_count(64);
+    let right_most = root_index.right_child().right_child().right_child();
+    let left_most = root_index.left_child().left_child().left_child();
+    assert!(accumulator.cached_node_index(right_most).is_some());
+    assert!(accumulator.cached_node_index(left_most).is_none());
+}
+
+#[test]
+fn test_warm_partly_full_cache() {
+    let leaves = create_leaves(0..64);
+    let mock_store = Arc::new(MockAccumulatorStore::new());
+    let accumulator = MerkleAccumulator::new_empty(mock_store.clone());
+    accumulator.append(&leaves).unwrap();
+    accumulator.flush().unwrap();
+    let info = accumulator.get_info();
+    let root = info.accumulator_root;
+
+    let accumulator = MerkleAccumulator::new_with_cache_config(
+        info,
+        mock_store,
+        AccumulatorCacheConfig { index_capacity: 10 },
+    );
+    // the oldest leaves, which the warm walk would not reach within the capacity.
+    let hot = create_leaf_nodes(0..4);
+    accumulator.cache_node_indexes(hot.clone());
+
+    assert_eq!(accumulator.warm_cache(root, u64::MAX).unwrap(), 6);
+    for node in &hot {
+        assert_eq!(
+            accumulator.cached_node_index(node.index()),
+            Some(node.hash())
+        );
+    }
+    let stats = accumulator.cache_stats();
+    assert_eq!(stats.insertions, 10);
+    assert_eq!(stats.evictions, 0);
+    // the cache is full, nothing more is loaded.
+    assert_eq!(accumulator.warm_cache(root, u64::MAX).unwrap(), 0);
+    assert_eq!(accumulator.cache_stats().evictions, 0);
+}
+
+#[test]
+fn test_num_leaves_and_nodes() {
+    let store = Arc::new(MockAccumulatorStore::new());
+    let accumulator = MerkleAccumulator::new_empty(store.clone());
+    assert_eq!(accumulator.num_leaves(), 0);
+    assert_eq!(accumulator.num_nodes(), 0);
+
+    let leaves = create_leaves(0..50);
+    accumulator.append(&leaves[..1]).unwrap();
+    assert_eq!((accumulator.num_leaves(), accumulator.num_nodes()), (1, 1));
+    accumulator.append(&leaves[1..]).unwrap();
+    // the frozen subtrees of 50 = 32 + 16 + 2 leaves have 63 + 31 + 3 nodes.
+    assert_eq!(
+        (accumulator.num_leaves(), accumulator.num_nodes()),
+        (50, 97)
+    );
+    accumulator.flush().unwrap();
+
+    // pruning deletes stored nodes, but not the size of the accumulator.
+    accumulator.prune(40).unwrap();
+    assert_eq!(
+        (accumulator.num_leaves(), accumulator.num_nodes()),
+        (50, 97)
+    );
+
+    let imported = MerkleAccumulator::new_empty(Arc::new(MockAccumulatorStore::new()));
+    imported
+        .import_frozen_subtrees(
+            50,
+            accumulator.export_frozen_subtrees().unwrap(),
+            accumulator.root_hash(),
+        )
+        .unwrap();
+    assert_eq!((imported.num_leaves(), imported.num_nodes()), (50, 97));
+    imported.append(&create_leaves(50..51)).unwrap();
+    assert_eq!((imported.num_leaves(), imported.num_nodes()), (51, 98));
+}
+
+#[test]
+fn test_rollback_to() {
+    let leaves = create_leaves(0..14);
+    let store = Arc::new(MockAccumulatorStore::new());
+    let accumulator = MerkleAccumulator::new_empty(store.clone());
+    let root_6 = accumulator.append(&leaves[..6]).unwrap();
+    accumulator.flush().unwrap();
+    let info_6 = accumulator.get_info();
+    let root_10 = accumulator.append(&leaves[6..10]).unwrap();
+    accumulator.flush().unwrap();
+    // the last leaves are not flushed before the rollback.
+    accumulator.append(&leaves[10..]).unwrap();
+
+    assert!(accumulator.rollback_to(15, false).is_err());
+    assert_eq!(accumulator.rollback_to(10, false).unwrap(), root_10);
+    assert_eq!(accumulator.rollback_to(6, true).unwrap(), root_6);
+    assert_eq!(accumulator.get_info(), info_6);
+    for leaf_index in 0..6 {
+        let leaf = accumulator.get_leaf(leaf_index).unwrap().unwrap();
+        assert_eq!(leaf, leaves[leaf_index as usize]);
+        let proof = accumulator.get_proof(leaf_index).unwrap().unwrap();
+        proof.verify(root_6, leaf, leaf_index).unwrap();
+    }
+    // the orphans of the rollback to 6 are deleted, the ones of the rollback to 10 are kept.
+    assert_eq!(
+        store.multiple_get(leaves[6..10].to_vec()).unwrap(),
+        vec![None; 4]
+    );
+    assert!(store
+        .multiple_get(leaves[..6].iter().chain(&leaves[10..]).copied().collect())
+        .unwrap()
+        .iter()
+        .all(Option::is_some));
+
+    // appending the same leaves again gives the same roots.
+    assert_eq!(accumulator.append(&leaves[6..10]).unwrap(), root_10);
+    assert_eq!(
+        accumulator.rollback_to(0, false).unwrap(),
+        *ACCUMULATOR_PLACEHOLDER_HASH
+    );
+    assert_eq!(accumulator.num_nodes(), 0);
+
+    // a size the accumulator never had, after appending all the leaves at once.
+    accumulator.append(&leaves).unwrap();
+    accumulator.flush().unwrap();
+    let root_7 = accumulator.rollback_to(7, false).unwrap();
+    let expected = MerkleAccumulator::new_empty(Arc::new(MockAccumulatorStore::new()));
+    assert_eq!(root_7, expected.append(&leaves[..7]).unwrap());
+    assert_eq!(accumulator.get_info(), expected.get_info());
+    accumulator.flush().unwrap();
+    let reloaded = MerkleAccumulator::new_with_info(accumulator.get_info(), store);
+    for leaf_index in 0..7 {
+        let proof = reloaded.get_proof(leaf_index).unwrap().unwrap();
+        proof
+            .verify(root_7, leaves[leaf_index as usize], leaf_index)
+            .unwrap();
+    }
+}
@@ -0,0 +1,51 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::tree_store::AccumulatorLeafDataStore;
+use crate::{Accumulator, MerkleAccumulator};
+use anyhow::Result;
+use starcoin_crypto::HashValue;
+use std::sync::Arc;
+
+/// A `MerkleAccumulator` which also keeps the original data of its leaves.
+/// Besides the tree nodes, every leaf costs the size of its data in the leaf data store,
+/// so only use it when the leaf data is not stored anywhere else.
+pub struct DataAccumulator {
+    accumulator: MerkleAccumulator,
+    data_store: Arc<dyn AccumulatorLeafDataStore>,
+}
+
+impl DataAccumulator {
+    pub fn new(
+        accumulator: MerkleAccumulator,
+        data_store: Arc<dyn AccumulatorLeafDataStore>,
+    ) -> Self {
+        Self {
+            accumulator,
+            data_store,
+        }
+    }
+
+    /// Append leaves with their data and return new root.
+    pub fn append(&self, leaves: &[(HashValue, Vec<u8>)]) -> Result<HashValue> {
+        let leaf_hashes: Vec<HashValue> = leaves.iter().map(|(hash, _)| *hash).collect();
+        // save the data first, so every leaf in the tree has its data.
+        self.data_store.save_leaf_data(leaves.to_vec())?;
+        self.accumulator.append(&leaf_hashes)
+    }
+
+    /// Get leaf data by index.
+    pub fn get_leaf_data(&self, leaf_index: u64) -> Result<Option<Vec<u8>>> {
+        if leaf_index >= self.accumulator.num_leaves() {
+            return Ok(None);
+        }
+        match self.accumulator.get_leaf(leaf_index)? {
+            Some(leaf_hash) => self.data_store.get_leaf_data(leaf_hash),
+            None => Ok(None),
+        }
+    }
+
+    pub fn accumulator(&self) -> &MerkleAccumulator {
+        &self.accumulator
+    }
+}
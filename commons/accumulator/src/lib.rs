@@ -1,22 +1,27 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 use crate::accumulator_info::AccumulatorInfo;
-use crate::node_index::NodeIndex;
-use crate::proof::AccumulatorProof;
+use crate::inmemory::InMemoryAccumulator;
+use crate::node_index::{FrozenSubTreeIterator, FrozenSubtreeSiblingIterator, NodeIndex};
+use crate::proof::{AccumulatorConsistencyProof, AccumulatorProof, AccumulatorRangeProof};
 use crate::tree::AccumulatorTree;
-use anyhow::{format_err, Result};
+use anyhow::{ensure, format_err, Result};
 pub use node::AccumulatorNode;
 use parking_lot::Mutex;
-use starcoin_crypto::HashValue;
+use rand::Rng;
+use starcoin_crypto::{hash::ACCUMULATOR_PLACEHOLDER_HASH, HashValue};
 #[cfg(test)]
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::sync::Arc;
 pub use tree_store::AccumulatorTreeStore;
 
 pub mod accumulator_info;
 #[cfg(test)]
 mod accumulator_test;
+pub mod data_accumulator;
 pub mod inmemory;
+pub mod metrics;
 pub mod node;
 pub mod node_index;
 pub mod proof;
@@ -30,6 +35,34 @@ pub const MAX_ACCUMULATOR_PROOF_DEPTH: usize = 63;
 pub const MAX_ACCUMULATOR_LEAVES: LeafCount = 1 << MAX_ACCUMULATOR_PROOF_DEPTH;
 pub const MAC_CACHE_SIZE: usize = 65535;
 
+/// The capacity of the caches of an accumulator.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct AccumulatorCacheConfig {
+    /// The max number of cached node index to node hash entries, zero disables the cache.
+    pub index_capacity: usize,
+}
+
+impl Default for AccumulatorCacheConfig {
+    fn default() -> Self {
+        Self {
+            index_capacity: MAC_CACHE_SIZE,
+        }
+    }
+}
+
+/// The counters of the index cache of an accumulator, the counters of all the accumulators are
+/// also exported by `AccumulatorMetrics`.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct AccumulatorCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub insertions: u64,
+    pub evictions: u64,
+}
+
+/// Max number of leaf proofs verified by `startup_integrity_check`.
+pub const MAX_INTEGRITY_CHECK_SAMPLES: u64 = 64;
+
 /// accumulator method define
 pub trait Accumulator {
     /// Append leaves and return new root
@@ -95,18 +128,346 @@ impl MerkleAccumulator {
         }
     }
 
+    pub fn new_with_cache_config(
+        acc_info: AccumulatorInfo,
+        node_store: Arc<dyn AccumulatorTreeStore>,
+        cache_config: AccumulatorCacheConfig,
+    ) -> Self {
+        Self {
+            tree: Mutex::new(AccumulatorTree::new_with_cache_config(
+                acc_info.frozen_subtree_roots,
+                acc_info.num_leaves,
+                acc_info.num_nodes,
+                acc_info.accumulator_root,
+                node_store,
+                cache_config,
+            )),
+        }
+    }
+
     pub fn new_empty(node_store: Arc<dyn AccumulatorTreeStore>) -> Self {
         Self {
             tree: Mutex::new(AccumulatorTree::new_empty(node_store)),
         }
     }
 
-    /// Fork a new accumulator base on current accumulator
+    /// Fork a new accumulator base on current accumulator, with the same cache config.
     pub fn fork(&self, info: Option<AccumulatorInfo>) -> MerkleAccumulator {
-        Self::new_with_info(
-            info.unwrap_or_else(|| self.get_info()),
-            self.tree.lock().store.clone(),
+        let (store, cache_config) = {
+            let tree = self.tree.lock();
+            (tree.store.clone(), tree.cache_config())
+        };
+        Self::new_with_cache_config(info.unwrap_or_else(|| self.get_info()), store, cache_config)
+    }
+
+    /// Get the proof that the accumulator of `to_size` leaves extends the one of `from_size`
+    /// leaves, both no bigger than this accumulator.
+    pub fn get_consistency_proof(
+        &self,
+        from_size: u64,
+        to_size: u64,
+    ) -> Result<AccumulatorConsistencyProof> {
+        ensure!(
+            from_size <= to_size,
+            "from_size {} is bigger than to_size {}",
+            from_size,
+            to_size
+        );
+        let num_leaves = self.num_leaves();
+        ensure!(
+            to_size <= num_leaves,
+            "to_size {} is bigger than the accumulator size {}",
+            to_size,
+            num_leaves
+        );
+        if from_size == to_size {
+            return Ok(AccumulatorConsistencyProof::default());
+        }
+        let get_hash = |index: NodeIndex| {
+            self.get_node_by_position(index.to_inorder_index())?
+                .ok_or_else(|| format_err!("Can not find accumulator node: {:?}", index))
+        };
+        let old_frozen_subtree_roots = FrozenSubTreeIterator::new(from_size)
+            .map(get_hash)
+            .collect::<Result<Vec<_>>>()?;
+        let subtrees = FrozenSubtreeSiblingIterator::new(from_size, to_size)
+            .map(get_hash)
+            .collect::<Result<Vec<_>>>()?;
+        Ok(AccumulatorConsistencyProof::new(
+            old_frozen_subtree_roots,
+            subtrees,
+        ))
+    }
+
+    /// Get the proof of the leaves `[start, end)` of this accumulator. Only the nodes of the
+    /// current size are available, so `root_size` must be the number of leaves.
+    pub fn get_range_proof(
+        &self,
+        root_size: u64,
+        start: u64,
+        end: u64,
+    ) -> Result<AccumulatorRangeProof> {
+        let num_leaves = self.num_leaves();
+        ensure!(
+            root_size == num_leaves,
+            "Range proof of root size {} is not supported, accumulator size is {}",
+            root_size,
+            num_leaves
+        );
+        ensure!(
+            start < end && end <= root_size,
+            "Invalid leaf range [{}, {}) of accumulator size {}",
+            start,
+            end,
+            root_size
+        );
+        let get_siblings = |leaf_index: u64| {
+            self.get_proof(leaf_index)?
+                .map(|proof| proof.siblings)
+                .ok_or_else(|| {
+                    format_err!("Can not get accumulator proof by index: {}", leaf_index)
+                })
+        };
+        let first_siblings = get_siblings(start)?;
+        let last_siblings = get_siblings(end - 1)?;
+        let mut left_siblings = vec![];
+        let mut right_siblings = vec![];
+        // once the two paths meet, both indexes are equal and only one side takes the sibling.
+        let (mut first_index, mut last_index) = (start, end - 1);
+        for (first_sibling, last_sibling) in first_siblings.into_iter().zip(last_siblings) {
+            if first_index % 2 == 1 {
+                left_siblings.push(first_sibling);
+            }
+            if last_index % 2 == 0 {
+                right_siblings.push(last_sibling);
+            }
+            first_index /= 2;
+            last_index /= 2;
+        }
+        Ok(AccumulatorRangeProof::new(left_siblings, right_siblings))
+    }
+
+    /// Export the frozen subtree roots with their positions, enough to rebuild the accumulator
+    /// without any other node.
+    pub fn export_frozen_subtrees(&self) -> Result<Vec<(NodeIndex, HashValue)>> {
+        let tree = self.tree.lock();
+        Ok(FrozenSubTreeIterator::new(tree.num_leaves)
+            .zip(tree.get_frozen_subtree_roots())
+            .collect())
+    }
+
+    /// Replace this accumulator with the one of `size` leaves built from exported frozen
+    /// subtrees, after checking they are the frozen subtrees of `size` and hash to `expected_root`.
+    /// New leaves can be appended on top of it, the older nodes are not required.
+    pub fn import_frozen_subtrees(
+        &self,
+        size: LeafCount,
+        subtrees: Vec<(NodeIndex, HashValue)>,
+        expected_root: HashValue,
+    ) -> Result<()> {
+        let indexes: Vec<NodeIndex> = subtrees.iter().map(|(index, _)| *index).collect();
+        ensure!(
+            indexes == FrozenSubTreeIterator::new(size).collect::<Vec<_>>(),
+            "Exported subtrees are not the frozen subtrees of accumulator size {}",
+            size
+        );
+        let frozen_subtree_roots: Vec<HashValue> =
+            subtrees.into_iter().map(|(_, hash)| hash).collect();
+        let root_hash = InMemoryAccumulator::new(frozen_subtree_roots.clone(), size)?.root_hash();
+        ensure!(
+            root_hash == expected_root,
+            "Frozen subtrees hash to {}, but expect root is {}",
+            root_hash,
+            expected_root
+        );
+        let mut tree = self.tree.lock();
+        let (store, cache_config, cache_counters) = (
+            tree.store.clone(),
+            tree.cache_config(),
+            tree.cache_counters(),
+        );
+        // every leaf and internal node of the subtrees is frozen.
+        let num_nodes = size * 2 - u64::from(size.count_ones());
+        *tree = AccumulatorTree::new_with_cache_config(
+            frozen_subtree_roots,
+            size,
+            num_nodes,
+            root_hash,
+            store,
+            cache_config,
         )
+        .with_cache_counters(cache_counters);
+        Ok(())
+    }
+
+    /// Truncate this accumulator back to its first `target_size` leaves, and return the root
+    /// hash of that size. Pending nodes are flushed first, the nodes rebuilt for `target_size`
+    /// are pending until the next flush. With `delete_orphans`, the nodes which only belong to
+    /// the truncated leaves are also deleted from the store, otherwise they are left in the
+    /// store, which is append only.
+    pub fn rollback_to(&self, target_size: LeafCount, delete_orphans: bool) -> Result<HashValue> {
+        let num_leaves = self.num_leaves();
+        ensure!(
+            target_size <= num_leaves,
+            "Rollback size {} is bigger than accumulator size {}",
+            target_size,
+            num_leaves
+        );
+        if target_size == num_leaves {
+            return Ok(self.root_hash());
+        }
+        self.flush()?;
+        let mut tree = self.tree.lock();
+        // the tree is rebuilt without the last leaf, then the leaf is appended again, so the not
+        // frozen nodes of `target_size` are saved even if the accumulator never had that size.
+        let (base_size, last_leaf) = match target_size.checked_sub(1) {
+            Some(base_size) => (
+                base_size,
+                tree.get_node_hash(NodeIndex::from_leaf_index(base_size))?,
+            ),
+            None => (0, None),
+        };
+        // the frozen subtrees of a smaller size are still frozen nodes in the current tree.
+        let frozen_subtree_roots = FrozenSubTreeIterator::new(base_size)
+            .map(|index| {
+                tree.get_node_hash(index)?
+                    .ok_or_else(|| format_err!("Can not find frozen subtree root {:?}", index))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let mut orphans = vec![];
+        if delete_orphans {
+            // every node covering a leaf in [target_size, num_leaves) has changed or gone.
+            for level in 0..=NodeIndex::root_level_from_leaf_count(num_leaves) {
+                for pos in (target_size >> level)..=((num_leaves - 1) >> level) {
+                    if let Some(hash) =
+                        tree.get_node_hash(NodeIndex::from_level_and_pos(level, pos))?
+                    {
+                        orphans.push(hash);
+                    }
+                }
+            }
+        }
+        let root_hash =
+            InMemoryAccumulator::new(frozen_subtree_roots.clone(), base_size)?.root_hash();
+        let (store, cache_config, cache_counters) = (
+            tree.store.clone(),
+            tree.cache_config(),
+            tree.cache_counters(),
+        );
+        let num_nodes = base_size * 2 - u64::from(base_size.count_ones());
+        *tree = AccumulatorTree::new_with_cache_config(
+            frozen_subtree_roots,
+            base_size,
+            num_nodes,
+            root_hash,
+            store,
+            cache_config,
+        )
+        .with_cache_counters(cache_counters);
+        if !orphans.is_empty() {
+            tree.delete_nodes(orphans)?;
+        }
+        let root_hash = match last_leaf {
+            Some(last_leaf) => tree.append(&[last_leaf])?,
+            None => root_hash,
+        };
+        Ok(root_hash)
+    }
+
+    /// Delete the internal nodes which are only needed to prove the leaves before
+    /// `keep_from_leaf`, and return the number of deleted nodes. The leaves, the frozen subtree
+    /// roots and the nodes on the path of any retained leaf are kept, so appends and the proofs
+    /// of the retained leaves are not affected. Pending nodes are flushed first.
+    pub fn prune(&self, keep_from_leaf: u64) -> Result<u64> {
+        let num_leaves = self.num_leaves();
+        ensure!(
+            keep_from_leaf <= num_leaves,
+            "keep_from_leaf {} is bigger than the accumulator size {}",
+            keep_from_leaf,
+            num_leaves
+        );
+        self.flush()?;
+        let store = self.tree.lock().store.clone();
+        let frozen_subtrees: HashSet<NodeIndex> = FrozenSubTreeIterator::new(num_leaves).collect();
+        let mut pruned = vec![];
+        // every internal node only covering leaves before `keep_from_leaf` is in these subtrees.
+        for subtree_root in FrozenSubTreeIterator::new(keep_from_leaf) {
+            if subtree_root.is_leaf() {
+                continue;
+            }
+            let mut hashes = vec![self
+                .get_node_by_position(subtree_root.to_inorder_index())?
+                .ok_or_else(|| format_err!("Can not find accumulator node: {:?}", subtree_root))?];
+            while !hashes.is_empty() {
+                let mut children = vec![];
+                // the nodes deleted by a previous prune are skipped.
+                for node in store.multiple_get(hashes)?.into_iter().flatten() {
+                    if let AccumulatorNode::Internal(internal) = node {
+                        if !internal.index().left_child().is_leaf() {
+                            children.push(internal.left());
+                            children.push(internal.right());
+                        }
+                        if !frozen_subtrees.contains(&internal.index()) {
+                            pruned.push(internal.hash());
+                        }
+                    }
+                }
+                hashes = children;
+            }
+        }
+        let pruned_count = pruned.len() as u64;
+        self.delete_nodes(pruned)?;
+        Ok(pruned_count)
+    }
+
+    /// Preload the index cache from the store, see `AccumulatorTree::warm_cache`.
+    /// Returns the number of entries loaded, at most `up_to_size` and the free cache capacity.
+    pub fn warm_cache(&self, accumulator_id: HashValue, up_to_size: u64) -> Result<usize> {
+        self.tree.lock().warm_cache(accumulator_id, up_to_size)
+    }
+
+    /// Get the proofs of `leaf_indices` in one walk of the tree. Proving n leaves of an
+    /// accumulator with root level h reads each node on their paths once, in h batch reads,
+    /// instead of walking the tree n times with up to h single reads each.
+    /// Only the nodes of the current size are available, so `root_size` must be the number of
+    /// leaves.
+    pub fn get_proofs(
+        &self,
+        root_size: u64,
+        leaf_indices: &[u64],
+    ) -> Result<Vec<AccumulatorProof>> {
+        let mut tree = self.tree.lock();
+        ensure!(
+            root_size == tree.num_leaves,
+            "Proof of root size {} is not supported, accumulator size is {}",
+            root_size,
+            tree.num_leaves
+        );
+        Ok(tree
+            .get_batch_siblings(leaf_indices)?
+            .into_iter()
+            .map(AccumulatorProof::new)
+            .collect())
+    }
+
+    /// Delete nodes from the store, and purge the index cache entries pointing to them.
+    pub fn delete_nodes(&self, node_hash_vec: Vec<HashValue>) -> Result<()> {
+        self.tree.lock().delete_nodes(node_hash_vec)
+    }
+
+    /// Snapshot the counters of the index cache.
+    pub fn cache_stats(&self) -> AccumulatorCacheStats {
+        self.tree.lock().cache_stats()
+    }
+
+    #[cfg(test)]
+    fn cache_node_indexes(&self, nodes: Vec<AccumulatorNode>) {
+        self.tree.lock().cache_node_indexes(nodes)
+    }
+
+    #[cfg(test)]
+    fn cached_node_index(&self, index: NodeIndex) -> Option<HashValue> {
+        self.tree.lock().cached_node_index(index)
     }
 
     #[cfg(test)]
@@ -212,3 +573,63 @@ impl Accumulator for MerkleAccumulator {
         )
     }
 }
+
+/// Check the accumulator with `expected_root` and `num_leaves` in `store` is intact:
+/// the root node is stored under its own hash, the frozen subtree roots recombine to the root,
+/// and the proofs of at most `MAX_INTEGRITY_CHECK_SAMPLES` leaves spread over the tree, from a
+/// random offset so every boot checks other leaves, and of the last leaf verify.
+pub fn startup_integrity_check(
+    store: Arc<dyn AccumulatorTreeStore>,
+    expected_root: HashValue,
+    num_leaves: LeafCount,
+) -> Result<()> {
+    if num_leaves == 0 {
+        ensure!(
+            expected_root == *ACCUMULATOR_PLACEHOLDER_HASH,
+            "Empty accumulator root mismatch, expect placeholder hash, but got {}",
+            expected_root
+        );
+        return Ok(());
+    }
+    let root_node = store
+        .get_node(expected_root)?
+        .ok_or_else(|| format_err!("Can not find accumulator root node: {}", expected_root))?;
+    ensure!(
+        root_node.hash() == expected_root,
+        "Accumulator root node hash mismatch, expect: {}, actual: {}",
+        expected_root,
+        root_node.hash()
+    );
+
+    let accumulator = MerkleAccumulator::new(expected_root, vec![], num_leaves, 0, store);
+    let frozen_subtree_roots = FrozenSubTreeIterator::new(num_leaves)
+        .map(|index| {
+            accumulator
+                .get_node_by_position(index.to_inorder_index())?
+                .ok_or_else(|| format_err!("Can not find frozen subtree root: {:?}", index))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let recombined_root = InMemoryAccumulator::new(frozen_subtree_roots, num_leaves)?.root_hash();
+    ensure!(
+        recombined_root == expected_root,
+        "Frozen subtree roots recombine to {}, but expect root is {}",
+        recombined_root,
+        expected_root
+    );
+
+    let step = (num_leaves + MAX_INTEGRITY_CHECK_SAMPLES - 1) / MAX_INTEGRITY_CHECK_SAMPLES;
+    let offset = rand::thread_rng().gen_range(0..step);
+    for leaf_index in (offset..num_leaves)
+        .step_by(step as usize)
+        .chain(std::iter::once(num_leaves - 1))
+    {
+        let leaf = accumulator
+            .get_leaf(leaf_index)?
+            .ok_or_else(|| format_err!("Can not find accumulator leaf by index: {}", leaf_index))?;
+        let proof = accumulator
+            .get_proof(leaf_index)?
+            .ok_or_else(|| format_err!("Can not get accumulator proof by index: {}", leaf_index))?;
+        proof.verify(expected_root, leaf, leaf_index)?;
+    }
+    Ok(())
+}
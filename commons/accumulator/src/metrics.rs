@@ -0,0 +1,108 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::AccumulatorCacheStats;
+use starcoin_metrics::{register, Opts, PrometheusError, Registry, UIntCounter, UIntCounterVec};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// The metrics of the index caches of the accumulators built on a store which reports them.
+#[derive(Clone)]
+pub struct AccumulatorMetrics {
+    pub index_cache_total: UIntCounterVec,
+    hits: UIntCounter,
+    misses: UIntCounter,
+    insertions: UIntCounter,
+    evictions: UIntCounter,
+}
+
+impl AccumulatorMetrics {
+    pub fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+        let index_cache_total = register(
+            UIntCounterVec::new(
+                Opts::new(
+                    "accumulator_index_cache_total",
+                    "Counters of the accumulator index cache hits, misses, insertions and evictions",
+                ),
+                &["event"],
+            )?,
+            registry,
+        )?;
+        Ok(Self {
+            hits: index_cache_total.with_label_values(&[CacheEvent::Hit.as_str()]),
+            misses: index_cache_total.with_label_values(&[CacheEvent::Miss.as_str()]),
+            insertions: index_cache_total.with_label_values(&[CacheEvent::Insertion.as_str()]),
+            evictions: index_cache_total.with_label_values(&[CacheEvent::Eviction.as_str()]),
+            index_cache_total,
+        })
+    }
+
+    fn counter(&self, event: CacheEvent) -> &UIntCounter {
+        match event {
+            CacheEvent::Hit => &self.hits,
+            CacheEvent::Miss => &self.misses,
+            CacheEvent::Insertion => &self.insertions,
+            CacheEvent::Eviction => &self.evictions,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum CacheEvent {
+    Hit,
+    Miss,
+    Insertion,
+    Eviction,
+}
+
+impl CacheEvent {
+    fn as_str(self) -> &'static str {
+        match self {
+            CacheEvent::Hit => "hit",
+            CacheEvent::Miss => "miss",
+            CacheEvent::Insertion => "insertion",
+            CacheEvent::Eviction => "eviction",
+        }
+    }
+}
+
+/// The counters of the index cache of an accumulator, kept when the tree is rebuilt.
+#[derive(Default)]
+pub(crate) struct CacheCounters {
+    hits: AtomicU64,
+    misses: AtomicU64,
+    insertions: AtomicU64,
+    evictions: AtomicU64,
+    metrics: Option<AccumulatorMetrics>,
+}
+
+impl CacheCounters {
+    pub(crate) fn new(metrics: Option<AccumulatorMetrics>) -> Self {
+        Self {
+            metrics,
+            ..Default::default()
+        }
+    }
+
+    /// Count the event, and report it to the metrics if any.
+    pub(crate) fn record(&self, event: CacheEvent) {
+        let counter = match event {
+            CacheEvent::Hit => &self.hits,
+            CacheEvent::Miss => &self.misses,
+            CacheEvent::Insertion => &self.insertions,
+            CacheEvent::Eviction => &self.evictions,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        if let Some(metrics) = self.metrics.as_ref() {
+            metrics.counter(event).inc();
+        }
+    }
+
+    pub(crate) fn snapshot(&self) -> AccumulatorCacheStats {
+        AccumulatorCacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            insertions: self.insertions.load(Ordering::Relaxed),
+            evictions: self.evictions.load(Ordering::Relaxed),
+        }
+    }
+}
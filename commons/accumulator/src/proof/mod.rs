@@ -1,12 +1,16 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::inmemory::{InMemoryAccumulator, MerkleTreeInternalNode};
 use crate::node::InternalNode;
 use crate::node_index::NodeIndex;
-use crate::MAX_ACCUMULATOR_PROOF_DEPTH;
-use anyhow::{ensure, Result};
+use crate::{Accumulator, MAX_ACCUMULATOR_PROOF_DEPTH};
+use anyhow::{ensure, format_err, Result};
+use lru::LruCache;
+use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
 use starcoin_crypto::HashValue;
+use std::sync::atomic::{AtomicU64, Ordering};
 
 #[derive(Default, Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
 pub struct AccumulatorProof {
@@ -80,3 +84,276 @@ impl AccumulatorProof {
         Ok(())
     }
 }
+
+/// An accumulator proof which carries the leaf count of the accumulator, so a verifier only
+/// needs the root hash to check it.
+#[derive(Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SelfContainedProof {
+    pub num_leaves: u64,
+    pub leaf_index: u64,
+    pub leaf: HashValue,
+    /// Siblings ordered from the bottom level to the root level, same as `AccumulatorProof`.
+    pub siblings: Vec<HashValue>,
+}
+
+impl SelfContainedProof {
+    pub fn new(num_leaves: u64, leaf_index: u64, leaf: HashValue, proof: AccumulatorProof) -> Self {
+        Self {
+            num_leaves,
+            leaf_index,
+            leaf,
+            siblings: proof.siblings,
+        }
+    }
+
+    /// Verifies the leaf exists in the accumulator whose root hash is `expected_root_hash`
+    /// and whose leaf count is `self.num_leaves`.
+    pub fn verify(&self, expected_root_hash: HashValue) -> Result<()> {
+        verify_leaf(
+            expected_root_hash,
+            self.num_leaves,
+            self.leaf_index,
+            self.leaf,
+            &self.siblings,
+        )
+    }
+}
+
+/// Verifies `leaf` is the leaf at `leaf_index` of the accumulator with `num_leaves` leaves
+/// whose root hash is `root`, with only the proof siblings and no access to any store.
+pub fn verify_leaf(
+    root: HashValue,
+    num_leaves: u64,
+    leaf_index: u64,
+    leaf: HashValue,
+    proof: &[HashValue],
+) -> Result<()> {
+    ensure!(
+        leaf_index < num_leaves,
+        "Leaf index {} out of range, num_leaves: {}.",
+        leaf_index,
+        num_leaves
+    );
+    let root_level = NodeIndex::root_from_leaf_count(num_leaves).level() as usize;
+    ensure!(
+        proof.len() == root_level,
+        "Accumulator proof with {} leaves should have {} siblings, got {}.",
+        num_leaves,
+        root_level,
+        proof.len()
+    );
+    AccumulatorProof::new(proof.to_vec()).verify(root, leaf, leaf_index)
+}
+
+/// A proof that the accumulator of `new_size` leaves is an extension of the one of `old_size`.
+/// It carries the frozen subtree roots of the old accumulator, so a verifier only needs the two
+/// root hashes, and the roots of the subtrees which are appended to it to get the new one.
+#[derive(Clone, Debug, Default, Hash, Eq, PartialEq, Serialize, Deserialize)]
+pub struct AccumulatorConsistencyProof {
+    pub old_frozen_subtree_roots: Vec<HashValue>,
+    /// The new subtree roots, ordered from left to right.
+    pub subtrees: Vec<HashValue>,
+}
+
+impl AccumulatorConsistencyProof {
+    pub fn new(old_frozen_subtree_roots: Vec<HashValue>, subtrees: Vec<HashValue>) -> Self {
+        Self {
+            old_frozen_subtree_roots,
+            subtrees,
+        }
+    }
+
+    /// Verifies the accumulator with `new_root` and `new_size` leaves extends the one with
+    /// `old_root` and `old_size` leaves.
+    pub fn verify(
+        &self,
+        old_root: HashValue,
+        old_size: u64,
+        new_root: HashValue,
+        new_size: u64,
+    ) -> Result<()> {
+        ensure!(
+            old_size <= new_size,
+            "Old accumulator size {} is bigger than new size {}.",
+            old_size,
+            new_size
+        );
+        if old_size == new_size {
+            ensure!(
+                self.subtrees.is_empty(),
+                "Accumulator of the same size should have no new subtrees, got {}.",
+                self.subtrees.len()
+            );
+            ensure!(
+                old_root == new_root,
+                "Accumulators of the same size {} have different roots: {:x}, {:x}.",
+                old_size,
+                old_root,
+                new_root
+            );
+            return Ok(());
+        }
+        let old_accumulator =
+            InMemoryAccumulator::new(self.old_frozen_subtree_roots.clone(), old_size)?;
+        ensure!(
+            old_accumulator.root_hash() == old_root,
+            "Old root hashes do not match. Actual root hash: {:x}. Expected root hash: {:x}.",
+            old_accumulator.root_hash(),
+            old_root
+        );
+        let new_accumulator =
+            old_accumulator.append_subtrees(&self.subtrees, new_size - old_size)?;
+        ensure!(
+            new_accumulator.root_hash() == new_root,
+            "New root hashes do not match. Actual root hash: {:x}. Expected root hash: {:x}.",
+            new_accumulator.root_hash(),
+            new_root
+        );
+        Ok(())
+    }
+}
+
+/// A proof that a range of consecutive leaves belongs to an accumulator. The leaves of the range
+/// and the nodes above them are recomputed by the verifier, so only the siblings on the left of
+/// the first leaf path and on the right of the last leaf path are carried.
+#[derive(Clone, Debug, Default, Hash, Eq, PartialEq, Serialize, Deserialize)]
+pub struct AccumulatorRangeProof {
+    /// Siblings on the left of the range, ordered from the bottom level to the root level.
+    pub left_siblings: Vec<HashValue>,
+    /// Siblings on the right of the range, ordered from the bottom level to the root level.
+    pub right_siblings: Vec<HashValue>,
+}
+
+impl AccumulatorRangeProof {
+    pub fn new(left_siblings: Vec<HashValue>, right_siblings: Vec<HashValue>) -> Self {
+        Self {
+            left_siblings,
+            right_siblings,
+        }
+    }
+
+    /// Verifies `leaf_hashes` are the leaves starting at `first_leaf_index` of the accumulator
+    /// whose root hash is `expected_root_hash`.
+    pub fn verify(
+        &self,
+        expected_root_hash: HashValue,
+        first_leaf_index: u64,
+        leaf_hashes: &[HashValue],
+    ) -> Result<()> {
+        ensure!(!leaf_hashes.is_empty(), "Range proof of no leaves.");
+        ensure!(
+            self.left_siblings.len() <= MAX_ACCUMULATOR_PROOF_DEPTH
+                && self.right_siblings.len() <= MAX_ACCUMULATOR_PROOF_DEPTH,
+            "Accumulator range proof has more than {} siblings on one side.",
+            MAX_ACCUMULATOR_PROOF_DEPTH
+        );
+        let mut left_siblings = self.left_siblings.iter();
+        let mut right_siblings = self.right_siblings.iter();
+        // the index of the first node of the current level at its level.
+        let mut first_index = first_leaf_index;
+        let mut hashes = leaf_hashes.to_vec();
+        while hashes.len() > 1 || left_siblings.len() > 0 || right_siblings.len() > 0 {
+            let mut parents = Vec::with_capacity(hashes.len() / 2 + 1);
+            let mut children = hashes.as_slice();
+            if first_index % 2 == 1 {
+                // the first node is a right child.
+                let left = *left_siblings
+                    .next()
+                    .ok_or_else(|| format_err!("Range proof has too few left siblings."))?;
+                parents.push(MerkleTreeInternalNode::new(left, children[0]).hash());
+                children = &children[1..];
+            }
+            let mut pairs = children.chunks_exact(2);
+            for pair in &mut pairs {
+                parents.push(MerkleTreeInternalNode::new(pair[0], pair[1]).hash());
+            }
+            if let [last] = pairs.remainder() {
+                // the last node is a left child.
+                let right = *right_siblings
+                    .next()
+                    .ok_or_else(|| format_err!("Range proof has too few right siblings."))?;
+                parents.push(MerkleTreeInternalNode::new(*last, right).hash());
+            }
+            hashes = parents;
+            first_index /= 2;
+        }
+        ensure!(
+            hashes[0] == expected_root_hash,
+            "Root hashes do not match. Actual root hash: {:x}. Expected root hash: {:x}.",
+            hashes[0],
+            expected_root_hash
+        );
+        Ok(())
+    }
+}
+
+struct ProofCacheInner {
+    root_hash: HashValue,
+    proofs: LruCache<u64, AccumulatorProof>,
+}
+
+/// A bounded LRU cache of the proofs of an accumulator, keyed by the leaf index.
+/// All the cached proofs are dropped when the accumulator root changes.
+pub struct ProofCache {
+    inner: Mutex<ProofCacheInner>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl ProofCache {
+    /// A zero `capacity` disables the cache, every proof is generated and counted as a miss.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            inner: Mutex::new(ProofCacheInner {
+                root_hash: HashValue::zero(),
+                proofs: LruCache::new(capacity),
+            }),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Get the proof of `leaf_index` from the cache, or generate it by `accumulator`.
+    pub fn get_proof(
+        &self,
+        accumulator: &dyn Accumulator,
+        leaf_index: u64,
+    ) -> Result<Option<AccumulatorProof>> {
+        let root_hash = accumulator.root_hash();
+        {
+            let mut inner = self.inner.lock();
+            if inner.root_hash != root_hash {
+                inner.proofs.clear();
+                inner.root_hash = root_hash;
+            } else if let Some(proof) = inner.proofs.get(&leaf_index) {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                return Ok(Some(proof.clone()));
+            }
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let proof = accumulator.get_proof(leaf_index)?;
+        if let Some(proof) = &proof {
+            let mut inner = self.inner.lock();
+            // a zero capacity disables the cache.
+            if inner.root_hash == root_hash && inner.proofs.cap() > 0 {
+                inner.proofs.put(leaf_index, proof.clone());
+            }
+        }
+        Ok(proof)
+    }
+
+    /// The number of proofs served from the cache.
+    pub fn hits(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    /// The number of proofs generated by the accumulator.
+    pub fn misses(&self) -> u64 {
+        self.misses.load(Ordering::Relaxed)
+    }
+
+    /// The max number of cached proofs.
+    pub fn capacity(&self) -> usize {
+        self.inner.lock().proofs.cap()
+    }
+}
@@ -1,17 +1,22 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0s
 
+use crate::metrics::{CacheCounters, CacheEvent};
+use crate::node::InternalNode;
 use crate::node_index::FrozenSubTreeIterator;
 use crate::node_index::{NodeIndex, MAX_ACCUMULATOR_PROOF_DEPTH};
 use crate::tree_store::NodeCacheKey;
-use crate::{AccumulatorNode, AccumulatorTreeStore, LeafCount, NodeCount, MAC_CACHE_SIZE};
-use anyhow::{bail, format_err, Result};
+use crate::{
+    AccumulatorCacheConfig, AccumulatorCacheStats, AccumulatorNode, AccumulatorTreeStore,
+    LeafCount, NodeCount,
+};
+use anyhow::{bail, ensure, format_err, Result};
 use lru::LruCache;
 use mirai_annotations::*;
 use starcoin_crypto::hash::ACCUMULATOR_PLACEHOLDER_HASH;
 use starcoin_crypto::HashValue;
 use starcoin_logger::prelude::*;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 pub struct AccumulatorTree {
@@ -25,6 +30,8 @@ pub struct AccumulatorTree {
     pub(crate) root_hash: HashValue,
     /// The index cache
     index_cache: LruCache<NodeCacheKey, HashValue>,
+    /// The counters of the index cache
+    index_cache_counters: Arc<CacheCounters>,
     /// The storage of accumulator.
     pub(crate) store: Arc<dyn AccumulatorTreeStore>,
     /// The temp update nodes
@@ -44,6 +51,24 @@ impl AccumulatorTree {
         num_nodes: NodeCount,
         root_hash: HashValue,
         store: Arc<dyn AccumulatorTreeStore>,
+    ) -> Self {
+        Self::new_with_cache_config(
+            frozen_subtree_roots,
+            num_leaves,
+            num_nodes,
+            root_hash,
+            store,
+            AccumulatorCacheConfig::default(),
+        )
+    }
+
+    pub fn new_with_cache_config(
+        frozen_subtree_roots: Vec<HashValue>,
+        num_leaves: LeafCount,
+        num_nodes: NodeCount,
+        root_hash: HashValue,
+        store: Arc<dyn AccumulatorTreeStore>,
+        cache_config: AccumulatorCacheConfig,
     ) -> Self {
         let frozen_subtrees = if !frozen_subtree_roots.is_empty() {
             FrozenSubTreeIterator::new(num_leaves)
@@ -54,7 +79,8 @@ impl AccumulatorTree {
         };
         let s = Self {
             frozen_subtree_roots,
-            index_cache: LruCache::new(MAC_CACHE_SIZE),
+            index_cache: LruCache::new(cache_config.index_capacity),
+            index_cache_counters: Arc::new(CacheCounters::new(store.metrics())),
             num_leaves,
             num_nodes,
             root_hash,
@@ -258,6 +284,89 @@ impl AccumulatorTree {
         Ok(siblings)
     }
 
+    /// Get the siblings of each of `leaf_indices`, same as `get_siblings`. The tree is walked
+    /// once from the root, reading the internal nodes on the paths of all the leaves with one
+    /// batch read per level, so a node shared by several paths is read only once.
+    pub(crate) fn get_batch_siblings(
+        &mut self,
+        leaf_indices: &[u64],
+    ) -> Result<Vec<Vec<HashValue>>> {
+        for leaf_index in leaf_indices {
+            ensure!(
+                *leaf_index < self.num_leaves,
+                "Leaf index {} out of range, num_leaves: {}",
+                leaf_index,
+                self.num_leaves
+            );
+        }
+        if leaf_indices.is_empty() {
+            return Ok(vec![]);
+        }
+        let root_level = NodeIndex::root_level_from_leaf_count(self.num_leaves);
+        let ancestor =
+            |leaf_index: u64, level: u32| NodeIndex::from_level_and_pos(level, leaf_index >> level);
+        let mut internal_nodes: HashMap<NodeIndex, InternalNode> = HashMap::new();
+        let mut level_hashes: HashMap<NodeIndex, HashValue> = HashMap::new();
+        level_hashes.insert(
+            NodeIndex::root_from_leaf_count(self.num_leaves),
+            self.root_hash,
+        );
+        for level in (1..=root_level).rev() {
+            let (indexes, hashes): (Vec<_>, Vec<_>) = level_hashes.drain().unzip();
+            let mut nodes = Vec::with_capacity(hashes.len());
+            let mut missing = vec![];
+            for hash in &hashes {
+                let node = self.update_nodes.get(hash).cloned();
+                if node.is_none() {
+                    missing.push(*hash);
+                }
+                nodes.push(node);
+            }
+            let mut stored = self.store.multiple_get(missing)?.into_iter();
+            for (index, node) in indexes.into_iter().zip(nodes) {
+                let node = match node {
+                    Some(node) => Some(node),
+                    None => stored.next().flatten(),
+                };
+                match node {
+                    Some(AccumulatorNode::Internal(internal)) => {
+                        internal_nodes.insert(index, internal);
+                    }
+                    _ => bail!("Can not find accumulator internal node: {:?}", index),
+                }
+            }
+            if level == 1 {
+                break;
+            }
+            for leaf_index in leaf_indices {
+                let child = ancestor(*leaf_index, level - 1);
+                let parent = &internal_nodes[&child.parent()];
+                let hash = if child.is_left_child() {
+                    parent.left()
+                } else {
+                    parent.right()
+                };
+                level_hashes.insert(child, hash);
+            }
+        }
+        Ok(leaf_indices
+            .iter()
+            .map(|leaf_index| {
+                (0..root_level)
+                    .map(|level| {
+                        let node = ancestor(*leaf_index, level);
+                        let parent = &internal_nodes[&node.parent()];
+                        if node.is_left_child() {
+                            parent.right()
+                        } else {
+                            parent.left()
+                        }
+                    })
+                    .collect()
+            })
+            .collect())
+    }
+
     /// Get node hash by index.
     pub(crate) fn get_node_hash(&mut self, node_index: NodeIndex) -> Result<Option<HashValue>> {
         let idx = self.rightmost_leaf_index();
@@ -288,7 +397,13 @@ impl AccumulatorTree {
     }
 
     fn get_node_index(&mut self, key: NodeCacheKey) -> Option<HashValue> {
-        self.index_cache.get(&key).copied()
+        let hash = self.index_cache.get(&key).copied();
+        if hash.is_some() {
+            self.index_cache_counters.record(CacheEvent::Hit);
+        } else {
+            self.index_cache_counters.record(CacheEvent::Miss);
+        }
+        hash
     }
 
     /// Get node hash always.
@@ -365,12 +480,23 @@ impl AccumulatorTree {
     }
 
     fn save_node_indexes(&mut self, nodes: Vec<AccumulatorNode>) {
-        let id = format!("{:p}", self);
-        let cache = &mut self.index_cache;
+        // a zero capacity disables the index cache.
+        if self.index_cache.cap() == 0 {
+            return;
+        }
         for node in nodes {
-            if let Some(old) = cache.put(node.index(), node.hash()) {
-                trace!("cache exist node hash: {}-{:?}-{:?}", id, node.index(), old);
-            }
+            self.put_node_index(node.index(), node.hash());
+        }
+    }
+
+    /// Put the entry to the index cache, and count the insertion and the eviction it causes.
+    fn put_node_index(&mut self, index: NodeCacheKey, hash: HashValue) {
+        if !self.index_cache.contains(&index) && self.index_cache.len() == self.index_cache.cap() {
+            self.index_cache_counters.record(CacheEvent::Eviction);
+        }
+        self.index_cache_counters.record(CacheEvent::Insertion);
+        if let Some(old) = self.index_cache.put(index, hash) {
+            trace!("cache exist node hash: {:p}-{:?}-{:?}", self, index, old);
         }
     }
 
@@ -394,6 +520,139 @@ impl AccumulatorTree {
         num_new_leaves * 2 + root_level as usize
     }
 
+    /// Delete nodes from the store, and evict the index cache entries of every index which
+    /// points to one of them, so the deleted hashes are not returned from the cache.
+    pub fn delete_nodes(&mut self, node_hash_vec: Vec<HashValue>) -> Result<()> {
+        let deleted: HashSet<HashValue> = node_hash_vec.iter().copied().collect();
+        let stale_indexes: Vec<NodeCacheKey> = self
+            .index_cache
+            .iter()
+            .filter(|(_, hash)| deleted.contains(hash))
+            .map(|(index, _)| *index)
+            .collect();
+        for index in stale_indexes {
+            self.index_cache.pop(&index);
+        }
+        self.update_nodes.retain(|hash, _| !deleted.contains(hash));
+        self.store.delete_nodes(node_hash_vec)
+    }
+
+    /// Preload the index cache, so the first lookups after a restart do not all miss and read
+    /// the store. The frozen subtree roots are loaded first, then the tree is walked down from
+    /// the root one level per batch read, right children first, so the most recently appended
+    /// nodes are loaded before the older ones. `accumulator_id` must be the root hash of this
+    /// accumulator, and at most `up_to_size` new entries are loaded. Loading stops when the
+    /// cache is full rather than evicting the entries already cached. Returns the number of
+    /// entries loaded.
+    pub fn warm_cache(&mut self, accumulator_id: HashValue, up_to_size: u64) -> Result<usize> {
+        ensure!(
+            accumulator_id == self.root_hash,
+            "Warm cache of accumulator {}, but the accumulator root is {}",
+            accumulator_id,
+            self.root_hash
+        );
+        let mut loaded = 0;
+        if self.index_cache.cap() == 0 || self.num_leaves == 0 {
+            return Ok(loaded);
+        }
+        let frozen_subtrees: Vec<(NodeIndex, HashValue)> =
+            FrozenSubTreeIterator::new(self.num_leaves)
+                .zip(self.frozen_subtree_roots.clone())
+                .collect();
+        let mut frontier = vec![(
+            NodeIndex::root_from_leaf_count(self.num_leaves),
+            self.root_hash,
+        )];
+        for (index, hash) in frozen_subtrees.into_iter().rev().chain(frontier.clone()) {
+            if !self.warm_node_index(index, hash, &mut loaded, up_to_size) {
+                return Ok(loaded);
+            }
+        }
+        while !frontier.is_empty() {
+            frontier.retain(|(index, _)| !index.is_leaf());
+            let mut nodes = Vec::with_capacity(frontier.len());
+            let mut missing = vec![];
+            for (_, hash) in &frontier {
+                let node = self.update_nodes.get(hash).cloned();
+                if node.is_none() {
+                    missing.push(*hash);
+                }
+                nodes.push(node);
+            }
+            let mut stored = self.store.multiple_get(missing)?.into_iter();
+            let mut children = vec![];
+            for node in nodes {
+                let node = match node {
+                    Some(node) => Some(node),
+                    None => stored.next().flatten(),
+                };
+                // the node may have been pruned from the store.
+                if let Some(AccumulatorNode::Internal(internal)) = node {
+                    let index = internal.index();
+                    children.push((index.right_child(), internal.right()));
+                    children.push((index.left_child(), internal.left()));
+                }
+            }
+            children.retain(|(_, hash)| *hash != *ACCUMULATOR_PLACEHOLDER_HASH);
+            for (index, hash) in &children {
+                if !self.warm_node_index(*index, *hash, &mut loaded, up_to_size) {
+                    return Ok(loaded);
+                }
+            }
+            frontier = children;
+        }
+        Ok(loaded)
+    }
+
+    /// Put the entry to the index cache for `warm_cache`, an entry already cached is refreshed.
+    /// Returns false if the entry is new and the cache is full or `up_to_size` entries are loaded.
+    fn warm_node_index(
+        &mut self,
+        index: NodeIndex,
+        hash: HashValue,
+        loaded: &mut usize,
+        up_to_size: u64,
+    ) -> bool {
+        if !self.index_cache.contains(&index) {
+            if self.index_cache.len() == self.index_cache.cap() || *loaded as u64 >= up_to_size {
+                return false;
+            }
+            *loaded += 1;
+        }
+        self.put_node_index(index, hash);
+        true
+    }
+
+    pub fn cache_config(&self) -> AccumulatorCacheConfig {
+        AccumulatorCacheConfig {
+            index_capacity: self.index_cache.cap(),
+        }
+    }
+
+    pub fn cache_stats(&self) -> AccumulatorCacheStats {
+        self.index_cache_counters.snapshot()
+    }
+
+    /// Keep counting to `counters`, for a tree rebuilt from another one.
+    pub(crate) fn with_cache_counters(mut self, counters: Arc<CacheCounters>) -> Self {
+        self.index_cache_counters = counters;
+        self
+    }
+
+    pub(crate) fn cache_counters(&self) -> Arc<CacheCounters> {
+        self.index_cache_counters.clone()
+    }
+
+    #[cfg(test)]
+    pub fn cache_node_indexes(&mut self, nodes: Vec<AccumulatorNode>) {
+        self.save_node_indexes(nodes)
+    }
+
+    #[cfg(test)]
+    pub fn cached_node_index(&self, index: NodeIndex) -> Option<HashValue> {
+        self.index_cache.peek(&index).copied()
+    }
+
     #[cfg(test)]
     pub fn get_index_frozen_subtrees(&self) -> HashMap<NodeIndex, HashValue> {
         self.index_frozen_subtrees.clone()
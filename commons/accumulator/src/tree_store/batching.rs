@@ -0,0 +1,162 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::metrics::AccumulatorMetrics;
+use crate::tree_store::CompactionStats;
+use crate::{AccumulatorNode, AccumulatorTreeStore};
+use anyhow::Result;
+use parking_lot::Mutex;
+use starcoin_crypto::HashValue;
+use starcoin_logger::prelude::*;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+struct Batch {
+    nodes: HashMap<HashValue, AccumulatorNode>,
+    /// The time the first node of the batch was buffered.
+    started: Option<Instant>,
+}
+
+/// A node store wrapper which buffers the saved nodes, and writes them to the inner store in one
+/// batch when `max_batch_size` nodes are buffered, or the oldest buffered node has waited
+/// `max_wait`. The window is only checked on save, call `flush` to write the rest.
+/// The buffered nodes are flushed on drop too.
+pub struct BatchingWriter<W: AccumulatorTreeStore> {
+    inner: W,
+    max_batch_size: usize,
+    max_wait: Duration,
+    batch: Mutex<Batch>,
+}
+
+impl<W: AccumulatorTreeStore> BatchingWriter<W> {
+    pub fn new(inner: W, max_batch_size: usize, max_wait: Duration) -> Self {
+        Self {
+            inner,
+            max_batch_size,
+            max_wait,
+            batch: Mutex::new(Batch {
+                nodes: HashMap::new(),
+                started: None,
+            }),
+        }
+    }
+
+    pub fn inner(&self) -> &W {
+        &self.inner
+    }
+
+    /// The number of nodes buffered and not written to the inner store.
+    pub fn buffered(&self) -> usize {
+        self.batch.lock().nodes.len()
+    }
+
+    /// Write all the buffered nodes to the inner store.
+    pub fn flush(&self) -> Result<()> {
+        let mut batch = self.batch.lock();
+        Self::flush_batch(&self.inner, &mut batch)
+    }
+
+    fn flush_batch(inner: &W, batch: &mut Batch) -> Result<()> {
+        if batch.nodes.is_empty() {
+            return Ok(());
+        }
+        let nodes: Vec<AccumulatorNode> = batch.nodes.values().cloned().collect();
+        inner.save_nodes(nodes)?;
+        batch.nodes.clear();
+        batch.started = None;
+        Ok(())
+    }
+
+    fn buffer_nodes(&self, nodes: Vec<AccumulatorNode>) -> Result<()> {
+        let mut batch = self.batch.lock();
+        if batch.started.is_none() {
+            batch.started = Some(Instant::now());
+        }
+        for node in nodes {
+            batch.nodes.insert(node.hash(), node);
+        }
+        let expired = batch
+            .started
+            .map_or(false, |started| started.elapsed() >= self.max_wait);
+        if batch.nodes.len() >= self.max_batch_size || expired {
+            Self::flush_batch(&self.inner, &mut batch)?;
+        }
+        Ok(())
+    }
+}
+
+impl<W: AccumulatorTreeStore> AccumulatorTreeStore for BatchingWriter<W> {
+    fn get_node(&self, hash: HashValue) -> Result<Option<AccumulatorNode>> {
+        if let Some(node) = self.batch.lock().nodes.get(&hash) {
+            return Ok(Some(node.clone()));
+        }
+        self.inner.get_node(hash)
+    }
+
+    /// The buffered nodes are answered from the batch, the others are read from the inner store
+    /// at once.
+    fn multiple_get(&self, hash_vec: Vec<HashValue>) -> Result<Vec<Option<AccumulatorNode>>> {
+        let mut nodes = Vec::with_capacity(hash_vec.len());
+        let mut inner_hashes = vec![];
+        let mut inner_positions = vec![];
+        {
+            let batch = self.batch.lock();
+            for (position, hash) in hash_vec.into_iter().enumerate() {
+                match batch.nodes.get(&hash) {
+                    Some(node) => nodes.push(Some(node.clone())),
+                    None => {
+                        nodes.push(None);
+                        inner_hashes.push(hash);
+                        inner_positions.push(position);
+                    }
+                }
+            }
+        }
+        if !inner_hashes.is_empty() {
+            let inner_nodes = self.inner.multiple_get(inner_hashes)?;
+            for (position, node) in inner_positions.into_iter().zip(inner_nodes) {
+                nodes[position] = node;
+            }
+        }
+        Ok(nodes)
+    }
+
+    fn save_node(&self, node: AccumulatorNode) -> Result<()> {
+        self.buffer_nodes(vec![node])
+    }
+
+    fn save_nodes(&self, nodes: Vec<AccumulatorNode>) -> Result<()> {
+        self.buffer_nodes(nodes)
+    }
+
+    fn delete_nodes(&self, node_hash_vec: Vec<HashValue>) -> Result<()> {
+        {
+            let mut batch = self.batch.lock();
+            for hash in &node_hash_vec {
+                batch.nodes.remove(hash);
+            }
+        }
+        self.inner.delete_nodes(node_hash_vec)
+    }
+
+    fn metrics(&self) -> Option<AccumulatorMetrics> {
+        self.inner.metrics()
+    }
+
+    fn iter_nodes(&self) -> Result<Box<dyn Iterator<Item = Result<AccumulatorNode>> + '_>> {
+        self.flush()?;
+        self.inner.iter_nodes()
+    }
+
+    fn compact(&self) -> Result<CompactionStats> {
+        self.inner.compact()
+    }
+}
+
+impl<W: AccumulatorTreeStore> Drop for BatchingWriter<W> {
+    fn drop(&mut self) {
+        if let Err(e) = self.flush() {
+            error!("Flush buffered accumulator nodes on drop failed: {:?}", e);
+        }
+    }
+}
@@ -1,6 +1,8 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::metrics::AccumulatorMetrics;
+use crate::tree_store::{AccumulatorLeafDataStore, CompactionStats};
 use crate::{AccumulatorNode, AccumulatorTreeStore};
 use anyhow::{bail, Result};
 use parking_lot::Mutex;
@@ -9,17 +11,29 @@ use std::collections::HashMap;
 
 pub struct MockAccumulatorStore {
     node_store: Mutex<HashMap<HashValue, AccumulatorNode>>,
+    leaf_data_store: Mutex<HashMap<HashValue, Vec<u8>>>,
+    metrics: Option<AccumulatorMetrics>,
 }
 
 impl MockAccumulatorStore {
     pub fn new() -> MockAccumulatorStore {
         MockAccumulatorStore {
             node_store: Mutex::new(HashMap::new()),
+            leaf_data_store: Mutex::new(HashMap::new()),
+            metrics: None,
+        }
+    }
+    pub fn new_with_metrics(metrics: Option<AccumulatorMetrics>) -> MockAccumulatorStore {
+        MockAccumulatorStore {
+            metrics,
+            ..Self::new()
         }
     }
     pub fn copy_from(&self) -> Self {
         Self {
             node_store: Mutex::new(self.node_store.lock().clone()),
+            leaf_data_store: Mutex::new(self.leaf_data_store.lock().clone()),
+            metrics: self.metrics.clone(),
         }
     }
 }
@@ -39,8 +53,19 @@ impl AccumulatorTreeStore for MockAccumulatorStore {
         }
     }
 
-    fn multiple_get(&self, _hash_vec: Vec<HashValue>) -> Result<Vec<Option<AccumulatorNode>>> {
-        unimplemented!()
+    /// Unlike `get_node`, a missing node is returned as `None` at its position instead of an
+    /// error, same as the storage backed store.
+    fn multiple_get(&self, hash_vec: Vec<HashValue>) -> Result<Vec<Option<AccumulatorNode>>> {
+        let map = self.node_store.lock();
+        Ok(hash_vec
+            .into_iter()
+            .map(|hash| map.get(&hash).cloned())
+            .collect())
+    }
+
+    fn iter_nodes(&self) -> Result<Box<dyn Iterator<Item = Result<AccumulatorNode>> + '_>> {
+        let nodes: Vec<AccumulatorNode> = self.node_store.lock().values().cloned().collect();
+        Ok(Box::new(nodes.into_iter().map(Ok)))
     }
 
     fn save_node(&self, node: AccumulatorNode) -> Result<()> {
@@ -62,4 +87,24 @@ impl AccumulatorTreeStore for MockAccumulatorStore {
         }
         Ok(())
     }
+
+    fn metrics(&self) -> Option<AccumulatorMetrics> {
+        self.metrics.clone()
+    }
+
+    /// The nodes are removed from the map on delete, there is nothing to compact.
+    fn compact(&self) -> Result<CompactionStats> {
+        Ok(CompactionStats::default())
+    }
+}
+
+impl AccumulatorLeafDataStore for MockAccumulatorStore {
+    fn get_leaf_data(&self, hash: HashValue) -> Result<Option<Vec<u8>>> {
+        Ok(self.leaf_data_store.lock().get(&hash).cloned())
+    }
+
+    fn save_leaf_data(&self, data: Vec<(HashValue, Vec<u8>)>) -> Result<()> {
+        self.leaf_data_store.lock().extend(data);
+        Ok(())
+    }
 }
@@ -1,12 +1,14 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::metrics::AccumulatorMetrics;
 use crate::node_index::NodeIndex;
 use crate::AccumulatorNode;
-use anyhow::Result;
+use anyhow::{bail, ensure, Result};
 use starcoin_crypto::HashValue;
 use std::any::type_name;
 
+pub mod batching;
 pub mod mock;
 
 pub trait AccumulatorTreeStore: std::marker::Send + std::marker::Sync {
@@ -25,6 +27,58 @@ pub trait AccumulatorTreeStore: std::marker::Send + std::marker::Sync {
     fn save_nodes(&self, nodes: Vec<AccumulatorNode>) -> Result<()>;
     ///delete node
     fn delete_nodes(&self, node_hash_vec: Vec<HashValue>) -> Result<()>;
+
+    /// The metrics the index caches of the accumulators on this store report to, None by default.
+    fn metrics(&self) -> Option<AccumulatorMetrics> {
+        None
+    }
+
+    /// Iterate over all the stored nodes, in no particular order. A store which can not scan
+    /// its nodes returns an error.
+    fn iter_nodes(&self) -> Result<Box<dyn Iterator<Item = Result<AccumulatorNode>> + '_>> {
+        bail!("{} does not support iterating nodes", self.store_type())
+    }
+
+    /// Reclaim the space of the deleted nodes, and report how much was reclaimed.
+    fn compact(&self) -> Result<CompactionStats>;
+}
+
+/// Copy all the nodes of `src` to `dst`, saving at most `batch_size` nodes at a time,
+/// and return the number of copied nodes.
+pub fn copy_store(
+    src: &dyn AccumulatorTreeStore,
+    dst: &dyn AccumulatorTreeStore,
+    batch_size: usize,
+) -> Result<u64> {
+    ensure!(batch_size > 0, "batch_size must be bigger than 0");
+    let mut copied = 0u64;
+    let mut batch = Vec::with_capacity(batch_size);
+    for node in src.iter_nodes()? {
+        batch.push(node?);
+        if batch.len() == batch_size {
+            copied += batch.len() as u64;
+            dst.save_nodes(std::mem::take(&mut batch))?;
+        }
+    }
+    if !batch.is_empty() {
+        copied += batch.len() as u64;
+        dst.save_nodes(batch)?;
+    }
+    Ok(copied)
+}
+
+/// The result of `AccumulatorTreeStore::compact`.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct CompactionStats {
+    pub reclaimed_bytes: u64,
+}
+
+/// Side table of an accumulator which keeps the original data of the leaves, by leaf hash.
+pub trait AccumulatorLeafDataStore: std::marker::Send + std::marker::Sync {
+    /// get leaf data by leaf hash
+    fn get_leaf_data(&self, hash: HashValue) -> Result<Option<Vec<u8>>>;
+    /// batch save leaf data
+    fn save_leaf_data(&self, data: Vec<(HashValue, Vec<u8>)>) -> Result<()>;
 }
 
 pub type NodeCacheKey = NodeIndex;
@@ -7,6 +7,7 @@ use starcoin_vm_runtime::metrics::VMMetrics;
 use starcoin_vm_runtime::starcoin_vm::StarcoinVM;
 use starcoin_vm_types::identifier::Identifier;
 use starcoin_vm_types::language_storage::{ModuleId, TypeTag};
+use starcoin_vm_types::state_view::{InMemoryStateView, RecordingStateView};
 use starcoin_vm_types::{state_view::StateView, vm_status::VMStatus};
 
 pub fn execute_transactions<S: StateView>(
@@ -66,3 +67,32 @@ pub fn execute_readonly_function<S: StateView>(
     let mut vm = StarcoinVM::new(metrics);
     vm.execute_readonly_function(chain_state, module, function_name, type_params, args)
 }
+
+/// Build a state view with only the state `txn` reads when executed on `full`, and the previous
+/// values of the keys it writes, so the transaction can be replayed without the full state.
+pub fn minimal_view_for(
+    txn: &SignedUserTransaction,
+    full: &dyn StateView,
+) -> Result<InMemoryStateView> {
+    let recorder = RecordingStateView::new(full);
+    let outputs = execute_transactions(
+        &recorder,
+        vec![Transaction::UserTransaction(txn.clone())],
+        None,
+    )?;
+    let mut values = recorder.into_reads();
+    for output in &outputs {
+        for (state_key, _) in output.write_set() {
+            if !values.contains_key(state_key) {
+                values.insert(state_key.clone(), full.get_state_value(state_key)?);
+            }
+        }
+    }
+    Ok(InMemoryStateView::new(
+        values
+            .into_iter()
+            .filter_map(|(state_key, value)| value.map(|value| (state_key, value)))
+            .collect(),
+        full.is_genesis(),
+    ))
+}
@@ -3,7 +3,7 @@
 
 use anyhow::anyhow;
 use anyhow::Result;
-use starcoin_executor::validate_transaction;
+use starcoin_executor::{execute_transactions, minimal_view_for, validate_transaction};
 use starcoin_logger::prelude::*;
 use starcoin_transaction_builder::{
     build_batch_script_function_same_amount, build_transfer_txn,
@@ -20,6 +20,7 @@ use starcoin_types::{
 };
 use starcoin_vm_types::account_config::genesis_address;
 use starcoin_vm_types::account_config::AccountResource;
+use starcoin_vm_types::account_config::DepositEvent;
 use starcoin_vm_types::genesis_config::ChainId;
 use starcoin_vm_types::on_chain_config::{ConsensusConfig, OnChainConfig};
 use starcoin_vm_types::state_view::StateView;
@@ -43,6 +44,7 @@ use starcoin_types::account_config::G_STC_TOKEN_CODE;
 use starcoin_vm_runtime::starcoin_vm::StarcoinVM;
 use starcoin_vm_types::account_config::core_code_address;
 use starcoin_vm_types::state_store::state_key::StateKey;
+use starcoin_vm_types::transaction::DryRunTransaction;
 use test_helper::txn::create_account_txn_sent_as_association;
 
 #[derive(Default)]
@@ -809,6 +811,58 @@ fn test_execute_transfer_txn() -> Result<()> {
     Ok(())
 }
 
+#[stest::test]
+fn test_dry_run_transfer_txn_events() -> Result<()> {
+    let (chain_state, net) = prepare_genesis();
+
+    let account1 = Account::new();
+    let txn1 = Transaction::UserTransaction(create_account_txn_sent_as_association(
+        &account1,
+        0,
+        STCUnit::STC.value_of(100).scaling(),
+        1,
+        &net,
+    ));
+    let output1 = execute_and_apply(&chain_state, txn1);
+    assert_eq!(KeptVMStatus::Executed, output1.status().status().unwrap());
+
+    let account2 = Account::new();
+    let amount = STCUnit::STC.value_of(1).scaling();
+    let raw_txn = raw_peer_to_peer_txn(
+        *account1.address(),
+        *account2.address(),
+        amount,
+        0,
+        1,
+        DEFAULT_MAX_GAS_AMOUNT,
+        G_STC_TOKEN_CODE.clone(),
+        net.time_service().now_secs() + DEFAULT_EXPIRATION_TIME,
+        net.chain_id(),
+    );
+
+    let mut vm = StarcoinVM::new(None);
+    let (_vm_status, output) = vm.dry_run_transaction(
+        &chain_state,
+        DryRunTransaction {
+            raw_txn,
+            public_key: account1.public_key(),
+        },
+    )?;
+    assert_eq!(KeptVMStatus::Executed, output.status().status().unwrap());
+    let deposit = output
+        .events()
+        .iter()
+        .find(|event| event.is::<DepositEvent>())
+        .expect("transfer should emit a deposit event");
+    assert_eq!(deposit.decode_event::<DepositEvent>()?.amount(), amount);
+
+    // nothing is committed by the dry run.
+    assert!(chain_state
+        .get_account_resource(*account2.address())?
+        .is_none());
+    Ok(())
+}
+
 #[stest::test]
 fn test_execute_multi_txn_with_same_account() -> Result<()> {
     let (chain_state, net) = prepare_genesis();
@@ -1051,3 +1105,29 @@ fn test_insufficient_balance_for_transaction_fee() -> Result<()> {
 
     Ok(())
 }
+
+#[stest::test]
+fn test_minimal_view_replay() -> Result<()> {
+    let (chain_state, net) = prepare_genesis();
+
+    let account1 = Account::new();
+    let txn = create_account_txn_sent_as_association(
+        &account1,
+        0,
+        STCUnit::STC.value_of(100).scaling(),
+        1,
+        &net,
+    );
+    let minimal_view = minimal_view_for(&txn, &chain_state)?;
+    assert!(!minimal_view.data().is_empty());
+
+    let txns = vec![Transaction::UserTransaction(txn)];
+    let full_output = execute_transactions(&chain_state, txns.clone(), None)?;
+    let replay_output = execute_transactions(&minimal_view, txns, None)?;
+    assert_eq!(full_output, replay_output);
+    assert_eq!(
+        KeptVMStatus::Executed,
+        replay_output[0].status().status().unwrap()
+    );
+    Ok(())
+}
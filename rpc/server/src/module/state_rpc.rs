@@ -1,7 +1,7 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::module::map_err;
+use crate::module::{map_err, to_invalid_param_err};
 use bcs_ext::BCSCodec;
 use futures::future::TryFutureExt;
 use futures::FutureExt;
@@ -28,6 +28,7 @@ use starcoin_vm_types::identifier::Identifier;
 use starcoin_vm_types::language_storage::{struct_tag_match, StructTag};
 use starcoin_vm_types::state_store::state_key::StateKey;
 use starcoin_vm_types::state_store::table::TableHandle;
+use starcoin_vm_types::state_view::validate_access_path;
 use std::collections::BTreeMap;
 use std::sync::Arc;
 
@@ -56,8 +57,12 @@ where
     S: ChainStateAsyncService,
 {
     fn get(&self, access_path: AccessPath) -> FutureResult<Option<Vec<u8>>> {
-        let fut = self.service.clone().get(access_path).map_err(map_err);
-        Box::pin(fut)
+        let service = self.service.clone();
+        let fut = async move {
+            validate_access_path(&access_path).map_err(to_invalid_param_err)?;
+            service.get(access_path).await.map_err(map_err)
+        };
+        Box::pin(fut.boxed())
     }
 
     fn get_state_node_by_node_hash(&self, key_hash: HashValue) -> FutureResult<Option<Vec<u8>>> {
@@ -70,25 +75,33 @@ where
     }
 
     fn get_with_proof(&self, access_path: AccessPath) -> FutureResult<StateWithProofView> {
-        let fut = self
-            .service
-            .clone()
-            .get_with_proof(access_path)
-            .map_ok(|p| p.into())
-            .map_err(map_err);
-        Box::pin(fut)
+        let service = self.service.clone();
+        let fut = async move {
+            validate_access_path(&access_path).map_err(to_invalid_param_err)?;
+            service
+                .get_with_proof(access_path)
+                .await
+                .map(|p| p.into())
+                .map_err(map_err)
+        };
+        Box::pin(fut.boxed())
     }
 
     fn get_with_proof_raw(&self, access_path: AccessPath) -> FutureResult<StrView<Vec<u8>>> {
-        let fut = self
-            .service
-            .clone()
-            .get_with_proof(access_path)
-            .map_ok(|p| {
-                StrView(bcs_ext::to_bytes(&p).expect("Serialize StateWithProof should success."))
-            })
-            .map_err(map_err);
-        Box::pin(fut)
+        let service = self.service.clone();
+        let fut = async move {
+            validate_access_path(&access_path).map_err(to_invalid_param_err)?;
+            service
+                .get_with_proof(access_path)
+                .await
+                .map(|p| {
+                    StrView(
+                        bcs_ext::to_bytes(&p).expect("Serialize StateWithProof should success."),
+                    )
+                })
+                .map_err(map_err)
+        };
+        Box::pin(fut.boxed())
     }
 
     fn get_account_state(&self, address: AccountAddress) -> FutureResult<Option<AccountState>> {
@@ -160,13 +173,16 @@ where
         access_path: AccessPath,
         state_root: HashValue,
     ) -> FutureResult<StateWithProofView> {
-        let fut = self
-            .service
-            .clone()
-            .get_with_proof_by_root(access_path, state_root)
-            .map_ok(|p| p.into())
-            .map_err(map_err);
-        Box::pin(fut)
+        let service = self.service.clone();
+        let fut = async move {
+            validate_access_path(&access_path).map_err(to_invalid_param_err)?;
+            service
+                .get_with_proof_by_root(access_path, state_root)
+                .await
+                .map(|p| p.into())
+                .map_err(map_err)
+        };
+        Box::pin(fut.boxed())
     }
 
     fn get_with_proof_by_root_raw(
@@ -174,15 +190,20 @@ where
         access_path: AccessPath,
         state_root: HashValue,
     ) -> FutureResult<StrView<Vec<u8>>> {
-        let fut = self
-            .service
-            .clone()
-            .get_with_proof_by_root(access_path, state_root)
-            .map_ok(|p| {
-                StrView(bcs_ext::to_bytes(&p).expect("Serialize StateWithProof should success."))
-            })
-            .map_err(map_err);
-        Box::pin(fut)
+        let service = self.service.clone();
+        let fut = async move {
+            validate_access_path(&access_path).map_err(to_invalid_param_err)?;
+            service
+                .get_with_proof_by_root(access_path, state_root)
+                .await
+                .map(|p| {
+                    StrView(
+                        bcs_ext::to_bytes(&p).expect("Serialize StateWithProof should success."),
+                    )
+                })
+                .map_err(map_err)
+        };
+        Box::pin(fut.boxed())
     }
 
     fn get_with_table_item_proof(
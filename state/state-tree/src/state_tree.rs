@@ -185,6 +185,31 @@ where
         Ok(new_root_hash)
     }
 
+    /// Compute the root hash after applying `updates` on the committed state of the tree, a
+    /// None value means delete the key. The nodes committed into the local cache are read too,
+    /// so the tree does not need to be flushed. Neither the tree nor the storage is changed,
+    /// and un-committed modification is not included.
+    pub fn compute_root_hash(&self, updates: Vec<(K, Option<Vec<u8>>)>) -> Result<HashValue> {
+        let cur_root_hash = self.root_hash();
+        if updates.is_empty() {
+            return Ok(cur_root_hash);
+        }
+        // the last update of a key wins, as in `commit`.
+        let updates = updates
+            .into_iter()
+            .map(|(k, v)| (k, v.map(Blob::from)))
+            .collect::<BTreeMap<_, _>>();
+        let cache_guard = self.cache.lock();
+        let reader = CachedTreeReader {
+            store: self.storage.as_ref(),
+            cache: &cache_guard,
+        };
+        let tree = JellyfishMerkleTree::new(&reader);
+        let (new_root_hash, _) =
+            tree.updates(Some(cur_root_hash), updates.into_iter().collect())?;
+        Ok(new_root_hash)
+    }
+
     /// check if there is data that has not been commit.
     pub fn is_dirty(&self) -> bool {
         self.updates.read().len() > 0
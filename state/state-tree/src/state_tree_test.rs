@@ -368,3 +368,29 @@ pub fn test_state_multi_commit_and_flush() -> Result<()> {
     assert_eq!(state.get(&hash_value3)?, None);
     Ok(())
 }
+
+#[test]
+pub fn test_compute_root_hash() -> Result<()> {
+    let s = MockStateNodeStore::new();
+    let state = StateTree::<HashValueKey>::new(Arc::new(s), None);
+    let key1: HashValueKey = HashValue::random().into();
+    let key2: HashValueKey = HashValue::random().into();
+    let key3: HashValueKey = HashValue::random().into();
+    state.put(key1, vec![1]);
+    state.put(key2, vec![2]);
+    state.commit()?;
+    // committed but not flushed.
+    let base_root = state.root_hash();
+
+    let updates = vec![(key1, Some(vec![11])), (key2, None), (key3, Some(vec![3]))];
+    let computed_root = state.compute_root_hash(updates)?;
+    assert_eq!(state.root_hash(), base_root);
+    assert!(!state.is_dirty());
+    assert_eq!(state.compute_root_hash(vec![])?, base_root);
+
+    state.put(key1, vec![11]);
+    state.remove(&key2);
+    state.put(key3, vec![3]);
+    assert_eq!(state.commit()?, computed_root);
+    Ok(())
+}
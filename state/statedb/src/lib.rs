@@ -27,8 +27,8 @@ use starcoin_vm_types::access_path::{DataPath, ModuleName};
 use starcoin_vm_types::account_config::table_handle_address;
 use starcoin_vm_types::language_storage::StructTag;
 use starcoin_vm_types::state_store::{state_key::StateKey, table::TableHandle};
-use starcoin_vm_types::state_view::StateView;
-use std::collections::HashSet;
+use starcoin_vm_types::state_view::{access_path_has_prefix, StateView, StateViewScan};
+use std::collections::{BTreeMap, HashSet};
 use std::convert::TryInto;
 use std::sync::Arc;
 use thiserror::Error;
@@ -191,6 +191,37 @@ impl AccountStateObject {
         Ok(())
     }
 
+    /// The account state after applying `updates` on the committed trees, a None value means
+    /// remove, see `StateTree::compute_root_hash`.
+    fn compute_state(&self, updates: Vec<(DataPath, Option<Vec<u8>>)>) -> Result<AccountState> {
+        let mut code_updates = vec![];
+        let mut resource_updates = vec![];
+        for (data_path, value) in updates {
+            match data_path {
+                DataPath::Code(module_name) => {
+                    if value.is_none() {
+                        bail!("Not supported remove code currently.");
+                    }
+                    code_updates.push((module_name, value));
+                }
+                DataPath::Resource(struct_tag) => resource_updates.push((struct_tag, value)),
+            }
+        }
+        let code_root = match self.code_tree.lock().as_ref() {
+            Some(code_tree) => Some(code_tree.compute_root_hash(code_updates)?),
+            None if code_updates.is_empty() => None,
+            None => Some(
+                StateTree::<ModuleName>::new(self.store.clone(), None)
+                    .compute_root_hash(code_updates)?,
+            ),
+        };
+        let resource_root = self
+            .resource_tree
+            .lock()
+            .compute_root_hash(resource_updates)?;
+        Ok(AccountState::new(code_root, resource_root))
+    }
+
     fn to_state_set(&self) -> Result<AccountStateSet> {
         let code_root = self
             .code_tree
@@ -262,6 +293,60 @@ impl ChainStateDB {
         Self::new(self.store.clone(), Some(state_root))
     }
 
+    /// Compute the state root after applying `writes` on the committed state, a None value means
+    /// delete the key. The result is the root `apply_write_set` and `commit` would produce,
+    /// the changes committed but not flushed yet are included, the un-committed ones are not.
+    /// Nothing is changed or written to the store.
+    pub fn compute_state_root(&self, writes: &[(StateKey, Option<Vec<u8>>)]) -> Result<HashValue> {
+        let mut account_updates: BTreeMap<AccountAddress, Vec<(DataPath, Option<Vec<u8>>)>> =
+            BTreeMap::new();
+        let mut table_updates: BTreeMap<TableHandle, Vec<(Vec<u8>, Option<Vec<u8>>)>> =
+            BTreeMap::new();
+        for (state_key, value) in writes {
+            match state_key {
+                StateKey::AccessPath(access_path) => account_updates
+                    .entry(access_path.address)
+                    .or_default()
+                    .push((access_path.path.clone(), value.clone())),
+                StateKey::TableItem(table_item) => table_updates
+                    .entry(table_item.handle)
+                    .or_default()
+                    .push((table_item.key.clone(), value.clone())),
+            }
+        }
+        if !table_updates.is_empty() {
+            let mut table_handle_roots = vec![];
+            for (handle, updates) in table_updates {
+                let root_hash = self
+                    .get_table_handle_state_object(&handle)?
+                    .compute_root_hash(updates)?;
+                table_handle_roots.push((handle, Some(root_hash.to_vec())));
+            }
+            let table_handles_root = self
+                .state_tree_table_handles
+                .compute_root_hash(table_handle_roots)?;
+            account_updates
+                .entry(table_handle_address())
+                .or_default()
+                .push((TABLE_PATH.clone(), Some(table_handles_root.to_vec())));
+        }
+
+        let mut account_states = vec![];
+        for (address, updates) in account_updates {
+            let account_state_object = match self.get_account_state_object_option(&address)? {
+                Some(account_state_object) => account_state_object,
+                // `apply_write_set` only creates the account for a value.
+                None if matches!(updates.first(), Some((_, None))) => {
+                    return Err(AccountNotExist(address).into());
+                }
+                None => Arc::new(AccountStateObject::empty_account(self.store.clone())),
+            };
+            let state = account_state_object.compute_state(updates)?;
+            account_states.push((address, Some(state.try_into()?)));
+        }
+        self.state_tree.compute_root_hash(account_states)
+    }
+
     fn new_state_tree<K: RawKey>(&self, root_hash: HashValue) -> StateTree<K> {
         StateTree::new(self.store.clone(), Some(root_hash))
     }
@@ -391,6 +476,42 @@ impl StateView for ChainStateDB {
     fn is_genesis(&self) -> bool {
         self.state_tree.is_genesis()
     }
+
+    /// The accounts of the committed state, in the order of their key hash.
+    fn iter_accounts(&self) -> Result<Box<dyn Iterator<Item = Result<AccountAddress>> + '_>> {
+        let iter = self.state_tree.dump_iter()?;
+        Ok(Box::new(iter.map(|item| item.map(|(address, _)| address))))
+    }
+}
+
+impl StateViewScan for ChainStateDB {
+    /// The access paths of the account of `prefix`, in the order of their key hash.
+    fn iter_prefix(
+        &self,
+        prefix: &AccessPath,
+    ) -> Result<Box<dyn Iterator<Item = Result<(AccessPath, Vec<u8>)>> + '_>> {
+        let data_type = prefix.path.data_type();
+        let state_set = self
+            .get_account_state_set(&prefix.address)?
+            .and_then(|account_state_set| account_state_set.data_set(data_type).cloned());
+        let entries: Vec<(Vec<u8>, Vec<u8>)> = state_set.map(Into::into).unwrap_or_default();
+        let address = prefix.address;
+        let prefix = prefix.clone();
+        let iter = entries.into_iter().filter_map(move |(key, value)| {
+            let data_path = match data_type {
+                DataType::CODE => ModuleName::decode_key(key.as_slice()).map(DataPath::Code),
+                DataType::RESOURCE => StructTag::decode_key(key.as_slice()).map(DataPath::Resource),
+            };
+            match data_path {
+                Ok(data_path) => {
+                    let access_path = AccessPath::new(address, data_path);
+                    access_path_has_prefix(&access_path, &prefix).then(|| Ok((access_path, value)))
+                }
+                Err(e) => Some(Err(e)),
+            }
+        });
+        Ok(Box::new(iter))
+    }
 }
 
 impl ChainStateReader for ChainStateDB {
@@ -709,6 +830,10 @@ impl TableHandleStateObject {
         self.state_tree.lock().root_hash()
     }
 
+    pub fn compute_root_hash(&self, updates: Vec<(Vec<u8>, Option<Vec<u8>>)>) -> Result<HashValue> {
+        self.state_tree.lock().compute_root_hash(updates)
+    }
+
     pub fn get(&self, key: &Vec<u8>) -> Result<Option<Vec<u8>>> {
         self.state_tree.lock().get(key)
     }
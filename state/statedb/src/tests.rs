@@ -55,6 +55,68 @@ fn test_state_proof() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_compute_state_root() -> Result<()> {
+    let chain_state_db = ChainStateDB::mock();
+    let access_path1 = AccessPath::random_resource();
+    let access_path2 = AccessPath::random_resource();
+    chain_state_db.apply_write_set(state_keys_to_write_set(
+        vec![
+            StateKey::AccessPath(access_path1.clone()),
+            StateKey::AccessPath(access_path2.clone()),
+        ],
+        vec![random_bytes(), random_bytes()],
+    ))?;
+    let flushed_root = chain_state_db.commit()?;
+    chain_state_db.flush()?;
+
+    // committed but not flushed, the new nodes are only in the local cache.
+    let access_path3 = AccessPath::random_resource();
+    chain_state_db.apply_write_set(to_write_set(access_path3.clone(), random_bytes()))?;
+    let base_root = chain_state_db.commit()?;
+    assert_ne!(base_root, flushed_root);
+
+    let writes = vec![
+        (StateKey::AccessPath(access_path1), Some(random_bytes())),
+        (StateKey::AccessPath(access_path2), None),
+        (StateKey::AccessPath(access_path3), Some(random_bytes())),
+        (
+            StateKey::AccessPath(AccessPath::random_resource()),
+            Some(random_bytes()),
+        ),
+        (
+            StateKey::TableItem(TableItem {
+                handle: TableHandle(AccountAddress::random()),
+                key: random_bytes(),
+            }),
+            Some(random_bytes()),
+        ),
+    ];
+    let computed_root = chain_state_db.compute_state_root(&writes)?;
+    assert_ne!(computed_root, base_root);
+    assert_eq!(chain_state_db.state_root(), base_root);
+
+    let write_set = WriteSetMut::new(
+        writes
+            .into_iter()
+            .map(|(key, value)| match value {
+                Some(value) => (key, WriteOp::Value(value)),
+                None => (key, WriteOp::Deletion),
+            })
+            .collect::<Vec<_>>(),
+    )
+    .freeze()?;
+    chain_state_db.apply_write_set(write_set)?;
+    let committed_root = chain_state_db.commit()?;
+    assert_eq!(computed_root, committed_root);
+
+    // a deletion does not create the account.
+    assert!(chain_state_db
+        .compute_state_root(&[(StateKey::AccessPath(AccessPath::random_resource()), None)])
+        .is_err());
+    Ok(())
+}
+
 #[test]
 fn test_state_db() -> Result<()> {
     let storage = MockStateNodeStore::new();
@@ -294,3 +356,42 @@ fn test_state_with_table_item_proof() -> Result<()> {
     state_with_table_item_proof3.verify(&handle2, key3.as_slice())?;
     Ok(())
 }
+
+#[test]
+fn test_iter_accounts() -> Result<()> {
+    let chain_state_db = ChainStateDB::mock();
+    let mut addresses = vec![];
+    for _ in 0..5 {
+        let access_path = AccessPath::random_resource();
+        addresses.push(access_path.address);
+        chain_state_db.apply_write_set(to_write_set(access_path, random_bytes()))?;
+    }
+    chain_state_db.commit()?;
+
+    let mut accounts = chain_state_db
+        .iter_accounts()?
+        .collect::<Result<Vec<AccountAddress>>>()?;
+    accounts.sort();
+    addresses.sort();
+    assert_eq!(accounts, addresses);
+    Ok(())
+}
+
+#[test]
+fn test_iter_prefix() -> Result<()> {
+    let chain_state_db = ChainStateDB::mock();
+    let access_path = AccessPath::random_resource();
+    let other_path = AccessPath::new(access_path.address, AccountResource::resource_path());
+    let value = random_bytes();
+    chain_state_db.apply_write_set(to_write_set(access_path.clone(), value.clone()))?;
+    chain_state_db.apply_write_set(to_write_set(other_path, random_bytes()))?;
+    chain_state_db.commit()?;
+
+    let entries = chain_state_db
+        .iter_prefix(&access_path)?
+        .collect::<Result<Vec<_>>>()?;
+    assert_eq!(entries, vec![(access_path.clone(), value)]);
+    let missing = AccessPath::random_resource();
+    assert_eq!(chain_state_db.iter_prefix(&missing)?.count(), 0);
+    Ok(())
+}
@@ -7,6 +7,8 @@ use crate::StorageInstance;
 use crate::{BLOCK_ACCUMULATOR_NODE_PREFIX_NAME, TRANSACTION_ACCUMULATOR_NODE_PREFIX_NAME};
 use anyhow::Result;
 use bcs_ext::BCSCodec;
+use starcoin_accumulator::metrics::AccumulatorMetrics;
+use starcoin_accumulator::tree_store::CompactionStats;
 use starcoin_accumulator::{AccumulatorNode, AccumulatorTreeStore};
 use starcoin_crypto::hash::HashValue;
 
@@ -40,14 +42,17 @@ where
     S: CodecKVStore<HashValue, AccumulatorNode>,
 {
     store: S,
+    metrics: Option<AccumulatorMetrics>,
 }
 
 impl AccumulatorStorage<BlockAccumulatorStorage> {
     pub fn new_block_accumulator_storage(
         instance: StorageInstance,
+        metrics: Option<AccumulatorMetrics>,
     ) -> AccumulatorStorage<BlockAccumulatorStorage> {
         Self {
             store: BlockAccumulatorStorage::new(instance),
+            metrics,
         }
     }
 }
@@ -55,9 +60,11 @@ impl AccumulatorStorage<BlockAccumulatorStorage> {
 impl AccumulatorStorage<TransactionAccumulatorStorage> {
     pub fn new_transaction_accumulator_storage(
         instance: StorageInstance,
+        metrics: Option<AccumulatorMetrics>,
     ) -> AccumulatorStorage<TransactionAccumulatorStorage> {
         Self {
             store: TransactionAccumulatorStorage::new(instance),
+            metrics,
         }
     }
 }
@@ -86,4 +93,20 @@ where
     fn delete_nodes(&self, node_hash_vec: Vec<HashValue>) -> Result<()> {
         self.store.delete_all(node_hash_vec)
     }
+
+    fn metrics(&self) -> Option<AccumulatorMetrics> {
+        self.metrics.clone()
+    }
+
+    fn iter_nodes(&self) -> Result<Box<dyn Iterator<Item = Result<AccumulatorNode>> + '_>> {
+        let mut iter = self.store.iter()?;
+        iter.seek_to_first();
+        Ok(Box::new(iter.map(|item| item.map(|(_, node)| node))))
+    }
+
+    fn compact(&self) -> Result<CompactionStats> {
+        Ok(CompactionStats {
+            reclaimed_bytes: self.store.compact()?,
+        })
+    }
 }
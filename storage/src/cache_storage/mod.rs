@@ -26,6 +26,10 @@ impl CacheStorage {
             metrics,
         }
     }
+
+    pub fn metrics(&self) -> Option<&StorageMetrics> {
+        self.metrics.as_ref()
+    }
 }
 
 impl Default for CacheStorage {
@@ -168,6 +168,10 @@ impl DBStorage {
         Ok(())
     }
 
+    pub fn metrics(&self) -> Option<&StorageMetrics> {
+        self.metrics.as_ref()
+    }
+
     /// Flushes all memtable data. This is only used for testing `get_approximate_sizes_cf` in unit
     /// tests.
     pub fn flush_all(&self) -> Result<()> {
@@ -178,6 +182,25 @@ impl DBStorage {
         Ok(())
     }
 
+    /// Flush and compact the whole column family, and return the bytes of the sst files the
+    /// compaction reclaimed.
+    pub fn compact_cf(&self, cf_name: &str) -> Result<u64> {
+        let cf_handle = self.get_cf_handle(cf_name)?;
+        self.db.flush_cf(cf_handle)?;
+        let before = self.sst_files_size(cf_handle)?;
+        self.db
+            .compact_range_cf(cf_handle, None::<&[u8]>, None::<&[u8]>);
+        let after = self.sst_files_size(cf_handle)?;
+        Ok(before.saturating_sub(after))
+    }
+
+    fn sst_files_size(&self, cf_handle: &rocksdb::ColumnFamily) -> Result<u64> {
+        Ok(self
+            .db
+            .property_int_value_cf(cf_handle, "rocksdb.total-sst-files-size")?
+            .unwrap_or_default())
+    }
+
     /// List cf
     pub fn list_cf(path: impl AsRef<Path>) -> Result<Vec<String>, Error> {
         Ok(rocksdb::DB::list_cf(&rocksdb::Options::default(), path)?)
@@ -280,6 +280,9 @@ pub struct Storage {
 
 impl Storage {
     pub fn new(instance: StorageInstance) -> Result<Self> {
+        let accumulator_metrics = instance
+            .metrics()
+            .map(|metrics| metrics.accumulator.clone());
         let storage = Self {
             transaction_info_storage: TransactionInfoStorage::new(instance.clone()),
             transaction_info_hash_storage: TransactionInfoHashStorage::new(instance.clone()),
@@ -288,9 +291,13 @@ impl Storage {
             state_node_storage: StateStorage::new(instance.clone()),
             block_accumulator_storage: AccumulatorStorage::new_block_accumulator_storage(
                 instance.clone(),
+                accumulator_metrics.clone(),
             ),
             transaction_accumulator_storage:
-                AccumulatorStorage::new_transaction_accumulator_storage(instance.clone()),
+                AccumulatorStorage::new_transaction_accumulator_storage(
+                    instance.clone(),
+                    accumulator_metrics,
+                ),
             block_info_storage: BlockInfoStorage::new(instance.clone()),
             event_storage: ContractEventStorage::new(instance.clone()),
             chain_info_storage: ChainInfoStorage::new(instance),
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2
 
 use anyhow::Result;
+use starcoin_accumulator::metrics::AccumulatorMetrics;
 use starcoin_metrics::{
     self, register, HistogramOpts, HistogramVec, Opts, PrometheusError, Registry, UIntCounterVec,
     UIntGauge,
@@ -15,6 +16,7 @@ pub struct StorageMetrics {
     pub storage_item_bytes: HistogramVec,
     pub storage_time: HistogramVec,
     pub cache_items: UIntGauge,
+    pub accumulator: AccumulatorMetrics,
 }
 
 impl StorageMetrics {
@@ -53,11 +55,14 @@ impl StorageMetrics {
             registry,
         )?;
 
+        let accumulator = AccumulatorMetrics::register(registry)?;
+
         Ok(Self {
             storage_rw_total,
             storage_item_bytes,
             storage_time,
             cache_items,
+            accumulator,
         })
     }
 }
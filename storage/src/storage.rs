@@ -4,6 +4,7 @@
 pub use crate::batch::WriteBatch;
 use crate::cache_storage::CacheStorage;
 use crate::db_storage::{DBStorage, SchemaIterator};
+use crate::metrics::StorageMetrics;
 use crate::upgrade::DBUpgrade;
 use anyhow::{bail, format_err, Result};
 use byteorder::{BigEndian, ReadBytesExt};
@@ -97,6 +98,16 @@ impl StorageInstance {
         }
     }
 
+    /// The metrics of the db, or of the cache if there is no db.
+    pub fn metrics(&self) -> Option<&StorageMetrics> {
+        match self {
+            StorageInstance::CACHE { cache } => cache.metrics(),
+            StorageInstance::DB { db } | StorageInstance::CacheAndDb { cache: _, db } => {
+                db.metrics()
+            }
+        }
+    }
+
     // make sure Arc::strong_count(&db) == 1 unless will get None
     pub fn db_mut(&mut self) -> Option<&mut DBStorage> {
         match self {
@@ -499,6 +510,10 @@ where
     fn get_raw(&self, key: K) -> Result<Option<Vec<u8>>>;
 
     fn iter(&self) -> Result<SchemaIterator<K, V>>;
+
+    /// Compact the storage, and return the reclaimed bytes. The cache frees the space of a
+    /// removed item at once, so only the db has something to reclaim.
+    fn compact(&self) -> Result<u64>;
 }
 
 impl KeyCodec for u64 {
@@ -642,4 +657,11 @@ where
             .ok_or_else(|| format_err!("Only support scan on db storage instance"))?;
         db.iter::<K, V>(self.get_store().prefix_name)
     }
+
+    fn compact(&self) -> Result<u64> {
+        match self.get_store().storage().db() {
+            Some(db) => db.compact_cf(self.get_store().prefix_name),
+            None => Ok(0),
+        }
+    }
 }
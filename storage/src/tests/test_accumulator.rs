@@ -44,3 +44,73 @@ fn test_storage() {
         .unwrap();
     assert_eq!(acc_node, acc_node3);
 }
+
+#[test]
+fn test_storage_reopen() {
+    let path = starcoin_config::temp_dir();
+    let new_storage = || {
+        Storage::new(StorageInstance::new_db_instance(
+            DBStorage::new(path.as_ref(), RocksdbConfig::default(), None).unwrap(),
+        ))
+        .unwrap()
+    };
+    let nodes = (0..10)
+        .map(|i| AccumulatorNode::new_leaf(NodeIndex::from_leaf_index(i), HashValue::random()))
+        .collect::<Vec<_>>();
+    let hashes = nodes.iter().map(|node| node.hash()).collect::<Vec<_>>();
+    {
+        let storage = new_storage();
+        storage
+            .transaction_accumulator_storage
+            .save_nodes(nodes.clone())
+            .unwrap();
+        storage
+            .transaction_accumulator_storage
+            .delete_nodes(hashes[5..].to_vec())
+            .unwrap();
+    }
+
+    let storage = new_storage();
+    let read_nodes = storage
+        .transaction_accumulator_storage
+        .multiple_get(hashes.clone())
+        .unwrap();
+    assert_eq!(read_nodes.len(), hashes.len());
+    for (i, node) in read_nodes.into_iter().enumerate() {
+        if i < 5 {
+            assert_eq!(node, Some(nodes[i].clone()));
+        } else {
+            assert!(node.is_none());
+        }
+    }
+    assert!(storage
+        .block_accumulator_storage
+        .get_node(hashes[0])
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn test_compact() {
+    let storage = Storage::new(StorageInstance::new_db_instance(
+        DBStorage::new(
+            starcoin_config::temp_dir().as_ref(),
+            RocksdbConfig::default(),
+            None,
+        )
+        .unwrap(),
+    ))
+    .unwrap();
+    let nodes = (0..100)
+        .map(|i| AccumulatorNode::new_leaf(NodeIndex::from_leaf_index(i), HashValue::random()))
+        .collect::<Vec<_>>();
+    let hashes = nodes.iter().map(|node| node.hash()).collect::<Vec<_>>();
+    let store = &storage.block_accumulator_storage;
+    store.save_nodes(nodes).unwrap();
+    // write the nodes to a sst file, so the compaction has something to reclaim.
+    store.compact().unwrap();
+    store.delete_nodes(hashes.clone()).unwrap();
+    assert!(store.compact().unwrap().reclaimed_bytes > 0);
+    assert!(store.get_node(hashes[0]).unwrap().is_none());
+    assert_eq!(store.compact().unwrap().reclaimed_bytes, 0);
+}
@@ -2,9 +2,13 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::Account;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use starcoin_config::ChainNetwork;
+use starcoin_crypto::keygen::KeyGen;
 use starcoin_transaction_builder::{
-    create_signed_txn_with_association_account, DEFAULT_MAX_GAS_AMOUNT,
+    build_batch_script_function, build_empty_script, create_signed_txn_with_association_account,
+    DEFAULT_MAX_GAS_AMOUNT,
 };
 use starcoin_txpool::TxPoolService;
 use starcoin_txpool_api::TxPoolSyncService;
@@ -14,6 +18,7 @@ use starcoin_vm_types::account_config::core_code_address;
 use starcoin_vm_types::account_config::stc_type_tag;
 use starcoin_vm_types::identifier::Identifier;
 use starcoin_vm_types::language_storage::ModuleId;
+use starcoin_vm_types::transaction::authenticator::{AccountPrivateKey, AccountPublicKey};
 use starcoin_vm_types::transaction::{ScriptFunction, TransactionPayload};
 
 const NEW_ACCOUNT_AMOUNT: u128 = 1_000_000_000;
@@ -133,3 +138,76 @@ pub fn create_account_txn_sent_as_association(
         net,
     )
 }
+
+/// The script functions cycled by `generate_calibration_transactions`:
+/// `EmptyScripts::empty_script` is the baseline of the prologue and epilogue,
+/// `TransferScripts::peer_to_peer_v2` covers arithmetic, global storage and events,
+/// `TransferScripts::batch_peer_to_peer_v2` covers vectors and loops with a variable length.
+pub const CALIBRATION_SCRIPT_FUNCTIONS: [&str; 3] =
+    ["empty_script", "peer_to_peer_v2", "batch_peer_to_peer_v2"];
+
+const CALIBRATION_EXPIRATION_TIMESTAMP_SECS: u64 = u32::MAX as u64;
+
+fn calibration_account(rng: &mut StdRng) -> Account {
+    let (private_key, public_key) = KeyGen::from_seed(rng.gen()).generate_keypair();
+    Account::with_keypair(
+        AccountPrivateKey::Single(private_key),
+        AccountPublicKey::single(public_key),
+        None,
+    )
+}
+
+/// Generate `count` gas calibration transactions, the same `seed` always generates the same
+/// transactions. All of them are sent by one account derived from the seed, with sequence
+/// numbers from 0, so the account must be funded before they are executed.
+pub fn generate_calibration_transactions(
+    net: &ChainNetwork,
+    seed: u64,
+    count: usize,
+) -> Vec<SignedUserTransaction> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let sender = calibration_account(&mut rng);
+    let receivers: Vec<Account> = (0..8).map(|_| calibration_account(&mut rng)).collect();
+    (0..count)
+        .map(|i| {
+            let payload = match i % CALIBRATION_SCRIPT_FUNCTIONS.len() {
+                0 => build_empty_script(),
+                1 => {
+                    let receiver = &receivers[rng.gen_range(0..receivers.len())];
+                    ScriptFunction::new(
+                        ModuleId::new(
+                            core_code_address(),
+                            Identifier::new("TransferScripts").unwrap(),
+                        ),
+                        Identifier::new("peer_to_peer_v2").unwrap(),
+                        vec![stc_type_tag()],
+                        vec![
+                            bcs_ext::to_bytes(receiver.address()).unwrap(),
+                            bcs_ext::to_bytes(&rng.gen_range(1..TRANSFER_AMOUNT)).unwrap(),
+                        ],
+                    )
+                }
+                _ => {
+                    let batch_size = rng.gen_range(1..=receivers.len());
+                    build_batch_script_function(
+                        receivers[..batch_size]
+                            .iter()
+                            .map(|receiver| *receiver.address())
+                            .collect(),
+                        (0..batch_size)
+                            .map(|_| rng.gen_range(1..TRANSFER_AMOUNT))
+                            .collect(),
+                    )
+                }
+            };
+            sender.create_signed_txn_with_args(
+                TransactionPayload::ScriptFunction(payload),
+                i as u64,
+                DEFAULT_MAX_GAS_AMOUNT,
+                1,
+                CALIBRATION_EXPIRATION_TIMESTAMP_SECS,
+                net.chain_id(),
+            )
+        })
+        .collect()
+}
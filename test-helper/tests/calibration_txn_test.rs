@@ -0,0 +1,31 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use starcoin_config::ChainNetwork;
+use starcoin_vm_types::transaction::TransactionPayload;
+use std::collections::HashSet;
+use test_helper::txn::{generate_calibration_transactions, CALIBRATION_SCRIPT_FUNCTIONS};
+
+#[stest::test]
+fn test_generate_calibration_transactions() {
+    let net = ChainNetwork::new_test();
+    let txns = generate_calibration_transactions(&net, 42, 30);
+    assert_eq!(txns.len(), 30);
+    assert_eq!(txns, generate_calibration_transactions(&net, 42, 30));
+    assert_ne!(txns, generate_calibration_transactions(&net, 43, 30));
+
+    let functions: HashSet<String> = txns
+        .iter()
+        .map(|txn| match txn.payload() {
+            TransactionPayload::ScriptFunction(script_function) => {
+                script_function.function().to_string()
+            }
+            _ => panic!("calibration transaction should be a script function"),
+        })
+        .collect();
+    let expected: HashSet<String> = CALIBRATION_SCRIPT_FUNCTIONS
+        .iter()
+        .map(|function| function.to_string())
+        .collect();
+    assert_eq!(functions, expected);
+}
@@ -4,6 +4,7 @@
 //! This module contains the official gas meter implementation, along with some top-level gas
 //! parameters and traits to help manipulate them.
 
+use anyhow::{ensure, format_err, Result};
 use std::collections::BTreeMap;
 
 pub(crate) const EXECUTION_GAS_MULTIPLIER: u64 = 1;
@@ -14,6 +15,9 @@ pub trait FromOnChainGasSchedule: Sized {
     /// `None` should be returned when the gas schedule is missing some required entries.
     /// Unused entries should be safely ignored.
     fn from_on_chain_gas_schedule(gas_schedule: &BTreeMap<String, u64>) -> Option<Self>;
+
+    /// The keys `from_on_chain_gas_schedule` can not do without, the optional keys excluded.
+    fn required_keys() -> Vec<String>;
 }
 
 /// A trait for converting to a list of entries of the on-chain gas schedule.
@@ -29,3 +33,121 @@ pub trait InitialGasSchedule: Sized {
     /// Returns the initial value of this type, which is used in the genesis.
     fn initial() -> Self;
 }
+
+/// The required keys of `T` which `gas_schedule` does not have, in order.
+fn missing_required_keys<T: FromOnChainGasSchedule>(
+    gas_schedule: &BTreeMap<String, u64>,
+) -> Vec<String> {
+    let mut missing: Vec<String> = T::required_keys()
+        .into_iter()
+        .filter(|key| !gas_schedule.contains_key(key))
+        .collect();
+    missing.sort();
+    missing
+}
+
+/// Same as `from_on_chain_gas_schedule`, but the error lists every missing required key.
+pub fn from_on_chain_gas_schedule_strict<T>(gas_schedule: &BTreeMap<String, u64>) -> Result<T>
+where
+    T: FromOnChainGasSchedule,
+{
+    if let Some(params) = T::from_on_chain_gas_schedule(gas_schedule) {
+        return Ok(params);
+    }
+    let missing = missing_required_keys::<T>(gas_schedule);
+    ensure!(
+        missing.is_empty(),
+        "The gas schedule misses {} keys: {}",
+        missing.len(),
+        missing.join(", ")
+    );
+    Err(format_err!("Invalid gas schedule"))
+}
+
+/// Same as `from_on_chain_gas_schedule`, but the missing required keys are filled from the
+/// initial value of `T`. Returns the parsed value and the keys filled with defaults, which the
+/// caller should warn about.
+pub fn from_on_chain_gas_schedule_lenient<T>(
+    gas_schedule: &BTreeMap<String, u64>,
+) -> Result<(T, Vec<String>)>
+where
+    T: FromOnChainGasSchedule + ToOnChainGasSchedule + InitialGasSchedule,
+{
+    let missing = missing_required_keys::<T>(gas_schedule);
+    let initial: BTreeMap<String, u64> = T::initial()
+        .to_on_chain_gas_schedule()
+        .into_iter()
+        .collect();
+    let mut filled = gas_schedule.clone();
+    for key in &missing {
+        filled.insert(key.clone(), initial[key]);
+    }
+    let params = T::from_on_chain_gas_schedule(&filled)
+        .ok_or_else(|| format_err!("Invalid gas schedule"))?;
+    Ok((params, missing))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TransactionGasParameters;
+
+    fn gas_schedule() -> BTreeMap<String, u64> {
+        let mut gas_schedule: BTreeMap<String, u64> = TransactionGasParameters::initial()
+            .to_on_chain_gas_schedule()
+            .into_iter()
+            .collect();
+        gas_schedule.insert("txn.intrinsic_gas_per_byte".to_string(), 16);
+        gas_schedule.remove("txn.min_transaction_gas_units");
+        gas_schedule.remove("txn.default_account_size");
+        // optional, not reported.
+        gas_schedule.remove("txn.gas_schedule_version");
+        gas_schedule
+    }
+
+    #[test]
+    fn test_required_keys() {
+        let required = TransactionGasParameters::required_keys();
+        let all = TransactionGasParameters::initial().to_on_chain_gas_schedule();
+        assert_eq!(required.len() + 1, all.len());
+        assert!(!required.contains(&"txn.gas_schedule_version".to_string()));
+    }
+
+    #[test]
+    fn test_from_on_chain_gas_schedule_strict() {
+        let err = from_on_chain_gas_schedule_strict::<TransactionGasParameters>(&gas_schedule())
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "The gas schedule misses 2 keys: txn.default_account_size, txn.min_transaction_gas_units"
+        );
+
+        let full = TransactionGasParameters::initial()
+            .to_on_chain_gas_schedule()
+            .into_iter()
+            .collect();
+        assert!(from_on_chain_gas_schedule_strict::<TransactionGasParameters>(&full).is_ok());
+    }
+
+    #[test]
+    fn test_from_on_chain_gas_schedule_lenient() {
+        let (params, defaulted) =
+            from_on_chain_gas_schedule_lenient::<TransactionGasParameters>(&gas_schedule())
+                .unwrap();
+        assert_eq!(
+            defaulted,
+            vec![
+                "txn.default_account_size".to_string(),
+                "txn.min_transaction_gas_units".to_string()
+            ]
+        );
+        let initial = TransactionGasParameters::initial();
+        assert_eq!(
+            params.min_transaction_gas_units,
+            initial.min_transaction_gas_units
+        );
+        assert_eq!(params.default_account_size, initial.default_account_size);
+        assert_eq!(u64::from(params.intrinsic_gas_per_byte), 16);
+        assert_eq!(params.gas_schedule_version, 0);
+    }
+}
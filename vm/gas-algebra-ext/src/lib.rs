@@ -1,9 +1,13 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use move_core_types::gas_algebra::{Arg, GasQuantity, UnitDiv};
+use anyhow::{format_err, Result};
+use move_core_types::gas_algebra::{Arg, GasQuantity, InternalGas, UnitDiv};
 pub use move_vm_test_utils::gas_schedule::GasCost;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::time::Duration;
 
 #[macro_use]
 pub mod natives;
@@ -21,10 +25,13 @@ mod nursery;
 mod table;
 mod transaction;
 
-pub use algebra::{FeePerGasUnit, Gas};
-pub use gas_meter::{FromOnChainGasSchedule, InitialGasSchedule, ToOnChainGasSchedule};
+pub use algebra::{FeePerGasUnit, Gas, GasScalingFactor};
+pub use gas_meter::{
+    from_on_chain_gas_schedule_lenient, from_on_chain_gas_schedule_strict, FromOnChainGasSchedule,
+    InitialGasSchedule, ToOnChainGasSchedule,
+};
 pub use instr::InstructionGasParameters;
-pub use transaction::TransactionGasParameters;
+pub use transaction::{effective_fee, TransactionGasParameters, LATEST_GAS_SCHEDULE_VERSION};
 
 /// Unit of abstract value size -- a conceptual measurement of the memory space a Move value occupies.
 pub enum AbstractValueUnit {}
@@ -72,6 +79,94 @@ pub struct GasConstants {
     pub default_account_size: u64,
 }
 
+/// A violated invariant of `GasConstants`.
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum GasConstantsError {
+    #[error("maximum_number_of_gas_units({maximum_number_of_gas_units}) * max_price_per_gas_unit({max_price_per_gas_unit}) must be less than u64::MAX")]
+    MaxGasFeeOverflow {
+        maximum_number_of_gas_units: u64,
+        max_price_per_gas_unit: u64,
+    },
+    #[error("min_price_per_gas_unit({min_price_per_gas_unit}) must not be bigger than max_price_per_gas_unit({max_price_per_gas_unit})")]
+    InvalidPriceRange {
+        min_price_per_gas_unit: u64,
+        max_price_per_gas_unit: u64,
+    },
+}
+
+impl GasConstants {
+    /// Check the invariants between the constants, which the gas charging relies on.
+    pub fn validate(&self) -> Result<(), GasConstantsError> {
+        match self
+            .maximum_number_of_gas_units
+            .checked_mul(self.max_price_per_gas_unit)
+        {
+            Some(max_fee) if max_fee < u64::MAX => {}
+            _ => {
+                return Err(GasConstantsError::MaxGasFeeOverflow {
+                    maximum_number_of_gas_units: self.maximum_number_of_gas_units,
+                    max_price_per_gas_unit: self.max_price_per_gas_unit,
+                })
+            }
+        }
+        if self.min_price_per_gas_unit > self.max_price_per_gas_unit {
+            return Err(GasConstantsError::InvalidPriceRange {
+                min_price_per_gas_unit: self.min_price_per_gas_unit,
+                max_price_per_gas_unit: self.max_price_per_gas_unit,
+            });
+        }
+        Ok(())
+    }
+
+    /// The constants as `(name, value)` pairs, in declaration order.
+    fn entries(&self) -> Vec<(&'static str, u64)> {
+        vec![
+            ("global_memory_per_byte_cost", self.global_memory_per_byte_cost),
+            ("global_memory_per_byte_write_cost", self.global_memory_per_byte_write_cost),
+            ("min_transaction_gas_units", self.min_transaction_gas_units),
+            ("large_transaction_cutoff", self.large_transaction_cutoff),
+            ("intrinsic_gas_per_byte", self.intrinsic_gas_per_byte),
+            ("maximum_number_of_gas_units", self.maximum_number_of_gas_units),
+            ("min_price_per_gas_unit", self.min_price_per_gas_unit),
+            ("max_price_per_gas_unit", self.max_price_per_gas_unit),
+            ("max_transaction_size_in_bytes", self.max_transaction_size_in_bytes),
+            ("gas_unit_scaling_factor", self.gas_unit_scaling_factor),
+            ("default_account_size", self.default_account_size),
+        ]
+    }
+
+    /// The constant named `name`, see `entries`.
+    fn entry_mut(&mut self, name: &str) -> Option<&mut u64> {
+        Some(match name {
+            "global_memory_per_byte_cost" => &mut self.global_memory_per_byte_cost,
+            "global_memory_per_byte_write_cost" => &mut self.global_memory_per_byte_write_cost,
+            "min_transaction_gas_units" => &mut self.min_transaction_gas_units,
+            "large_transaction_cutoff" => &mut self.large_transaction_cutoff,
+            "intrinsic_gas_per_byte" => &mut self.intrinsic_gas_per_byte,
+            "maximum_number_of_gas_units" => &mut self.maximum_number_of_gas_units,
+            "min_price_per_gas_unit" => &mut self.min_price_per_gas_unit,
+            "max_price_per_gas_unit" => &mut self.max_price_per_gas_unit,
+            "max_transaction_size_in_bytes" => &mut self.max_transaction_size_in_bytes,
+            "gas_unit_scaling_factor" => &mut self.gas_unit_scaling_factor,
+            "default_account_size" => &mut self.default_account_size,
+            _ => return None,
+        })
+    }
+
+    /// The wall-clock time one unit of computational gas is calibrated against, see
+    /// `maximum_number_of_gas_units`.
+    pub const MICROS_PER_GAS_UNIT: u64 = 5;
+
+    /// Convert a measured execution time to the internal gas it is expected to cost, assuming
+    /// one gas unit per `MICROS_PER_GAS_UNIT` microseconds, rounded down.
+    /// This is an estimate for calibration tooling, the charged gas never depends on time.
+    pub fn duration_to_gas(&self, d: Duration) -> InternalGas {
+        let units = u64::try_from(d.as_micros() / u128::from(Self::MICROS_PER_GAS_UNIT))
+            .unwrap_or(u64::MAX);
+        units.saturating_mul(self.gas_unit_scaling_factor).into()
+    }
+}
+
 /// The cost tables, keyed by the serialized form of the bytecode instruction.  We use the
 /// serialized form as opposed to the instruction enum itself as the key since this will be the
 /// on-chain representation of bytecode instructions in the future.
@@ -81,3 +176,309 @@ pub struct CostTable {
     pub native_table: Vec<GasCost>,
     pub gas_constants: GasConstants,
 }
+
+impl CostTable {
+    /// The json form of the table, the field names are the same as the on-chain `VMConfig`
+    /// and every cost is a plain integer, so it can be edited by hand.
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Parse a table from the json form of `to_json`, every field is required.
+    pub fn from_json(json: &str) -> Result<CostTable> {
+        serde_json::from_str(json).map_err(|e| format_err!("Invalid cost table json: {}", e))
+    }
+
+    /// A copy of this table with the entries of `overrides` replaced, for what-if analysis.
+    /// The entries are named like in `diff`. An unknown name, or constants which do not pass
+    /// `GasConstants::validate` are an error.
+    pub fn with_overrides(&self, overrides: &GasParamOverrides) -> Result<CostTable> {
+        let mut cost_table = self.clone();
+        for (name, value) in &overrides.values {
+            *cost_table
+                .entry_mut(name)
+                .ok_or_else(|| format_err!("Unknown gas parameter: {}", name))? = *value;
+        }
+        cost_table.gas_constants.validate()?;
+        Ok(cost_table)
+    }
+
+    fn entry_mut(&mut self, name: &str) -> Option<&mut u64> {
+        if let Some(constant) = name.strip_prefix("gas_constants.") {
+            return self.gas_constants.entry_mut(constant);
+        }
+        let (table, rest) = if let Some(rest) = name.strip_prefix("instruction_table[") {
+            (&mut self.instruction_table, rest)
+        } else {
+            (&mut self.native_table, name.strip_prefix("native_table[")?)
+        };
+        let (idx, field) = rest.split_once("].")?;
+        let cost = table.get_mut(idx.parse::<usize>().ok()?)?;
+        match field {
+            "instruction_gas" => Some(&mut cost.instruction_gas),
+            "memory_gas" => Some(&mut cost.memory_gas),
+            _ => None,
+        }
+    }
+
+    /// Compare `other` with this table entry by entry. The table entries are named by their
+    /// position, like `instruction_table[3].instruction_gas`, the constants by their field name,
+    /// like `gas_constants.max_price_per_gas_unit`. An entry only one of the tables has is
+    /// reported with `None` on the other side.
+    pub fn diff(&self, other: &CostTable) -> GasScheduleDiff {
+        let mut changes = vec![];
+        let fields = |cost: Option<&GasCost>| {
+            [
+                ("instruction_gas", cost.map(|c| c.instruction_gas)),
+                ("memory_gas", cost.map(|c| c.memory_gas)),
+            ]
+        };
+        for (table_name, old_table, new_table) in [
+            (
+                "instruction_table",
+                &self.instruction_table,
+                &other.instruction_table,
+            ),
+            ("native_table", &self.native_table, &other.native_table),
+        ] {
+            for idx in 0..std::cmp::max(old_table.len(), new_table.len()) {
+                for ((field, old_value), (_, new_value)) in fields(old_table.get(idx))
+                    .into_iter()
+                    .zip(fields(new_table.get(idx)))
+                {
+                    if old_value != new_value {
+                        changes.push(GasScheduleChange {
+                            name: format!("{}[{}].{}", table_name, idx, field),
+                            old_value,
+                            new_value,
+                        });
+                    }
+                }
+            }
+        }
+        for ((name, old_value), (_, new_value)) in self
+            .gas_constants
+            .entries()
+            .into_iter()
+            .zip(other.gas_constants.entries())
+        {
+            if old_value != new_value {
+                changes.push(GasScheduleChange {
+                    name: format!("gas_constants.{}", name),
+                    old_value: Some(old_value),
+                    new_value: Some(new_value),
+                });
+            }
+        }
+        GasScheduleDiff { changes }
+    }
+}
+
+/// Gas parameters to replace with `CostTable::with_overrides`, keyed by the entry name.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GasParamOverrides {
+    pub values: BTreeMap<String, u64>,
+}
+
+impl GasParamOverrides {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set(mut self, name: impl Into<String>, value: u64) -> Self {
+        self.values.insert(name.into(), value);
+        self
+    }
+}
+
+/// A changed entry between two `CostTable`s, see `CostTable::diff`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GasScheduleChange {
+    pub name: String,
+    /// `None` if the old table does not have the entry.
+    pub old_value: Option<u64>,
+    /// `None` if the new table does not have the entry.
+    pub new_value: Option<u64>,
+}
+
+/// The changes between two `CostTable`s, displayed one change per line.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GasScheduleDiff {
+    pub changes: Vec<GasScheduleChange>,
+}
+
+impl GasScheduleDiff {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+impl fmt::Display for GasScheduleChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = |v: Option<u64>| v.map_or_else(|| "none".to_string(), |v| v.to_string());
+        write!(
+            f,
+            "{}: {} -> {}",
+            self.name,
+            value(self.old_value),
+            value(self.new_value)
+        )
+    }
+}
+
+impl fmt::Display for GasScheduleDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for change in &self.changes {
+            writeln!(f, "{}", change)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gas_constants(gas_unit_scaling_factor: u64) -> GasConstants {
+        GasConstants {
+            global_memory_per_byte_cost: 4,
+            global_memory_per_byte_write_cost: 9,
+            min_transaction_gas_units: 600,
+            large_transaction_cutoff: 600,
+            intrinsic_gas_per_byte: 8,
+            maximum_number_of_gas_units: 40_000_000,
+            min_price_per_gas_unit: 1,
+            max_price_per_gas_unit: 10_000,
+            max_transaction_size_in_bytes: 128 * 1024,
+            gas_unit_scaling_factor,
+            default_account_size: 800,
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(gas_constants(1).validate(), Ok(()));
+
+        let mut constants = gas_constants(1);
+        constants.maximum_number_of_gas_units = u64::MAX / 10_000;
+        assert_eq!(constants.validate(), Ok(()));
+        constants.maximum_number_of_gas_units = u64::MAX / 10_000 + 1;
+        assert_eq!(
+            constants.validate(),
+            Err(GasConstantsError::MaxGasFeeOverflow {
+                maximum_number_of_gas_units: u64::MAX / 10_000 + 1,
+                max_price_per_gas_unit: 10_000,
+            })
+        );
+
+        let mut constants = gas_constants(1);
+        constants.min_price_per_gas_unit = constants.max_price_per_gas_unit;
+        assert_eq!(constants.validate(), Ok(()));
+        constants.min_price_per_gas_unit = constants.max_price_per_gas_unit + 1;
+        assert_eq!(
+            constants.validate(),
+            Err(GasConstantsError::InvalidPriceRange {
+                min_price_per_gas_unit: 10_001,
+                max_price_per_gas_unit: 10_000,
+            })
+        );
+    }
+
+    #[test]
+    fn test_cost_table_diff() {
+        let old = CostTable {
+            instruction_table: vec![GasCost::new(1, 1), GasCost::new(2, 1)],
+            native_table: vec![GasCost::new(10, 1)],
+            gas_constants: gas_constants(1),
+        };
+        assert!(old.diff(&old).is_empty());
+
+        let mut new = old.clone();
+        new.instruction_table[1] = GasCost::new(3, 1);
+        new.gas_constants.max_price_per_gas_unit = 20_000;
+        let diff = old.diff(&new);
+        assert_eq!(
+            diff.changes,
+            vec![
+                GasScheduleChange {
+                    name: "instruction_table[1].instruction_gas".to_string(),
+                    old_value: Some(2),
+                    new_value: Some(3),
+                },
+                GasScheduleChange {
+                    name: "gas_constants.max_price_per_gas_unit".to_string(),
+                    old_value: Some(10_000),
+                    new_value: Some(20_000),
+                },
+            ]
+        );
+        assert_eq!(
+            diff.to_string(),
+            "instruction_table[1].instruction_gas: 2 -> 3\n\
+             gas_constants.max_price_per_gas_unit: 10000 -> 20000\n"
+        );
+
+        new.native_table.push(GasCost::new(5, 2));
+        let diff = new.diff(&old);
+        assert_eq!(diff.changes.len(), 4);
+        assert_eq!(
+            diff.changes[1].to_string(),
+            "native_table[1].instruction_gas: 5 -> none"
+        );
+        assert_eq!(
+            diff.changes[2].to_string(),
+            "native_table[1].memory_gas: 2 -> none"
+        );
+    }
+
+    #[test]
+    fn test_cost_table_with_overrides() {
+        let cost_table = CostTable {
+            instruction_table: vec![GasCost::new(1, 1), GasCost::new(2, 1)],
+            native_table: vec![GasCost::new(10, 1)],
+            gas_constants: gas_constants(1),
+        };
+        let overrides = GasParamOverrides::new()
+            .set("gas_constants.intrinsic_gas_per_byte", 16)
+            .set("native_table[0].memory_gas", 3);
+        let new = cost_table.with_overrides(&overrides).unwrap();
+        assert_eq!(new.gas_constants.intrinsic_gas_per_byte, 16);
+        assert_eq!(new.native_table[0], GasCost::new(10, 3));
+        assert_eq!(cost_table.diff(&new).changes.len(), 2);
+
+        for name in [
+            "gas_constants.unknown",
+            "native_table[1].memory_gas",
+            "instruction_table[0].unknown",
+            "unknown",
+        ] {
+            let overrides = GasParamOverrides::new().set(name, 1);
+            assert!(cost_table.with_overrides(&overrides).is_err(), "{}", name);
+        }
+        let overrides =
+            GasParamOverrides::new().set("gas_constants.min_price_per_gas_unit", 10_001);
+        assert!(cost_table.with_overrides(&overrides).is_err());
+    }
+
+    #[test]
+    fn test_duration_to_gas() {
+        let constants = gas_constants(1);
+        assert_eq!(
+            constants.duration_to_gas(Duration::from_millis(1)),
+            InternalGas::new(200)
+        );
+        assert_eq!(
+            constants.duration_to_gas(Duration::from_micros(4)),
+            InternalGas::new(0)
+        );
+        // 40_000_000 gas units are 200 seconds at 5 microseconds per unit.
+        assert_eq!(
+            constants.duration_to_gas(Duration::from_secs(200)),
+            InternalGas::new(constants.maximum_number_of_gas_units)
+        );
+        assert_eq!(
+            gas_constants(1000).duration_to_gas(Duration::from_millis(1)),
+            InternalGas::new(200_000)
+        );
+    }
+}
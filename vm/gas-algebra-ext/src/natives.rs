@@ -69,6 +69,23 @@ macro_rules! expand_kv_for_native_gas_params {
     }
 }
 
+macro_rules! expand_required_key_for_native_gas_params {
+    (test_only $(.$field: ident)+, optional $key: literal, $initial_val: expr, $package_name: literal) => {
+        #[cfg(feature = "testing")]
+        None
+    };
+    (test_only $(.$field: ident)+, $key: literal, $initial_val: expr, $package_name: literal) => {
+        #[cfg(feature = "testing")]
+        Some(format!("{}.{}", $package_name, $key))
+    };
+    ($(.$field: ident)+, optional $key: literal, $initial_val: expr, $package_name: literal) => {
+        None
+    };
+    ($(.$field: ident)+, $key: literal, $initial_val: expr, $package_name: literal) => {
+        Some(format!("{}.{}", $package_name, $key))
+    };
+}
+
 #[cfg(test)]
 macro_rules! extract_key_for_native_gas_params {
     (test_only $(.$field: ident)+, $(optional)? $key: literal, $initial_val: expr) => {
@@ -103,6 +120,11 @@ macro_rules! define_gas_parameters_for_natives {
 
                 Some(params)
             }
+
+            fn required_keys() -> Vec<String> {
+                let keys: Vec<Option<String>> = [$(crate::natives::expand_required_key_for_native_gas_params!($($t)*, $package_name)),*].into_iter().collect();
+                keys.into_iter().flatten().collect()
+            }
         }
 
         impl crate::gas_meter::ToOnChainGasSchedule for $param_ty {
@@ -161,6 +183,7 @@ pub(crate) use define_gas_parameters_for_natives;
 pub(crate) use expand_get_for_native_gas_params;
 pub(crate) use expand_get_impl_for_native_gas_params;
 pub(crate) use expand_kv_for_native_gas_params;
+pub(crate) use expand_required_key_for_native_gas_params;
 pub(crate) use expand_set_for_native_gas_params;
 
 #[cfg(test)]
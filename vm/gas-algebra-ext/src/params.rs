@@ -12,6 +12,15 @@ macro_rules! expand_get_for_gas_parameters {
     };
 }
 
+macro_rules! expand_required_key_for_gas_parameters {
+    ($prefix: literal, optional $key: literal) => {
+        None
+    };
+    ($prefix: literal, $key: literal) => {
+        Some(format!("{}.{}", $prefix, $key))
+    };
+}
+
 macro_rules! define_gas_parameters {
     (
         $params_name: ident,
@@ -35,6 +44,11 @@ macro_rules! define_gas_parameters {
 
                 Some(params)
             }
+
+            fn required_keys() -> Vec<String> {
+                let keys: Vec<Option<String>> = [$($crate::params::expand_required_key_for_gas_parameters!($prefix, $(optional $($dummy)?)? $key)),*].into_iter().collect();
+                keys.into_iter().flatten().collect()
+            }
         }
 
         impl $crate::gas_meter::ToOnChainGasSchedule for $params_name {
@@ -72,6 +86,7 @@ macro_rules! define_gas_parameters {
 
 pub(crate) use define_gas_parameters;
 pub(crate) use expand_get_for_gas_parameters;
+pub(crate) use expand_required_key_for_gas_parameters;
 
 #[cfg(test)]
 mod tests {
@@ -102,4 +117,9 @@ mod tests {
             None
         ));
     }
+
+    #[test]
+    fn optional_should_not_be_required() {
+        assert_eq!(GasParameters::required_keys(), vec!["test.foo".to_string()]);
+    }
 }
@@ -5,10 +5,14 @@
 //! in the genesis and a mapping between the Rust representation and the on-chain gas schedule.
 
 use crate::algebra::{FeePerGasUnit, Gas, GasScalingFactor, GasUnit};
+use crate::GasConstants;
 use move_core_types::gas_algebra::{
     InternalGas, InternalGasPerByte, InternalGasUnit, NumBytes, ToUnitFractionalWithParams,
     ToUnitWithParams,
 };
+/// The version of the gas schedule defined by the initial gas parameters, bump it when they change.
+pub const LATEST_GAS_SCHEDULE_VERSION: u64 = 1;
+
 // see starcoin/config/src/genesis_config.rs G_GAS_CONSTANTS_V2
 // convert from https://github.com/starcoinorg/starcoin-framework/blob/main/sources/VMConfig.move#GasConstants
 // modify should with impl From<VMConfig> for GasSchedule
@@ -80,6 +84,14 @@ crate::params::define_gas_parameters!(
         ],
         // For V1 all accounts will be ~800 bytes
         [default_account_size: NumBytes, "default_account_size", 800],
+        // Tells which gas schedule a node has loaded. It is zero if the on-chain schedule
+        // predates it. The on-chain schedule is a `CostTable`, which has no slot for it, so
+        // every chain loads zero until the schedule moves to a key-value map.
+        [
+            gas_schedule_version: u64,
+            optional "gas_schedule_version",
+            LATEST_GAS_SCHEDULE_VERSION
+        ],
     ]
 );
 
@@ -110,6 +122,50 @@ impl TransactionGasParameters {
     pub fn cal_write_set_gas(&self) -> InternalGas {
         self.global_memory_per_byte_write_cost * self.default_account_size
     }
+
+    /// The mempool ordering score of a transaction, higher is better.
+    /// A transaction up to `large_transaction_cutoff` bytes scores its gas price scaled by the
+    /// cutoff, a larger one is discounted in proportion to its size.
+    /// Equal scores are not broken here, the pool keeps them in submission order.
+    pub fn priority_score(&self, gas_price: FeePerGasUnit, txn_size: NumBytes) -> u64 {
+        let cutoff = std::cmp::max(u64::from(self.large_transaction_cutoff), 1);
+        let size = std::cmp::max(u64::from(txn_size), cutoff);
+        let score = u128::from(u64::from(gas_price)) * u128::from(cutoff) * u128::from(cutoff)
+            / u128::from(size);
+        u64::try_from(score).unwrap_or(u64::MAX)
+    }
+}
+
+/// The fee in NanoSTC paid by a transaction which used `gas_used` internal gas at `gas_price`.
+/// The internal gas is converted to gas units with `scaling` and rounded up, the same as the
+/// gas used in the transaction output. There is no gas refund after execution, the charged
+/// fee is the net fee.
+pub fn effective_fee(
+    gas_used: InternalGas,
+    gas_price: FeePerGasUnit,
+    scaling: GasScalingFactor,
+) -> u64 {
+    let scaling = u128::from(std::cmp::max(u64::from(scaling), 1));
+    let gas_units = (u128::from(u64::from(gas_used)) + scaling - 1) / scaling;
+    u64::try_from(gas_units * u128::from(u64::from(gas_price))).unwrap_or(u64::MAX)
+}
+
+impl From<&TransactionGasParameters> for GasConstants {
+    fn from(params: &TransactionGasParameters) -> Self {
+        Self {
+            global_memory_per_byte_cost: params.global_memory_per_byte_cost.into(),
+            global_memory_per_byte_write_cost: params.global_memory_per_byte_write_cost.into(),
+            min_transaction_gas_units: params.min_transaction_gas_units.into(),
+            large_transaction_cutoff: params.large_transaction_cutoff.into(),
+            intrinsic_gas_per_byte: params.intrinsic_gas_per_byte.into(),
+            maximum_number_of_gas_units: params.maximum_number_of_gas_units.into(),
+            min_price_per_gas_unit: params.min_price_per_gas_unit.into(),
+            max_price_per_gas_unit: params.max_price_per_gas_unit.into(),
+            max_transaction_size_in_bytes: params.max_transaction_size_in_bytes.into(),
+            gas_unit_scaling_factor: params.gas_unit_scaling_factor.into(),
+            default_account_size: params.default_account_size.into(),
+        }
+    }
 }
 
 impl ToUnitWithParams<InternalGasUnit> for GasUnit {
@@ -127,3 +183,61 @@ impl ToUnitFractionalWithParams<GasUnit> for InternalGasUnit {
         (1, params.scaling_factor().into())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FromOnChainGasSchedule, InitialGasSchedule, ToOnChainGasSchedule};
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_priority_score() {
+        let params = TransactionGasParameters::initial();
+        let high_price_small = params.priority_score(10.into(), NumBytes::new(200));
+        let low_price_large = params.priority_score(1.into(), NumBytes::new(60_000));
+        assert!(high_price_small > low_price_large);
+
+        // the size does not matter below the cutoff.
+        assert_eq!(
+            params.priority_score(1.into(), NumBytes::new(100)),
+            params.priority_score(1.into(), params.large_transaction_cutoff)
+        );
+        // the same price scores lower for a larger transaction.
+        assert!(
+            params.priority_score(10.into(), NumBytes::new(6_000))
+                < params.priority_score(10.into(), NumBytes::new(600))
+        );
+        // a tenfold price makes up for a tenfold size.
+        assert_eq!(
+            params.priority_score(10.into(), NumBytes::new(6_000)),
+            params.priority_score(1.into(), NumBytes::new(600))
+        );
+    }
+
+    #[test]
+    fn test_effective_fee() {
+        let price: FeePerGasUnit = 2.into();
+        assert_eq!(effective_fee(InternalGas::new(1000), price, 1.into()), 2000);
+        // the partial gas unit is charged as a whole one.
+        assert_eq!(effective_fee(InternalGas::new(1001), price, 10.into()), 202);
+        assert_eq!(effective_fee(InternalGas::new(1000), price, 10.into()), 200);
+        // a zero scaling factor is treated as 1.
+        assert_eq!(effective_fee(InternalGas::new(1000), price, 0.into()), 2000);
+        assert_eq!(effective_fee(InternalGas::new(0), price, 10.into()), 0);
+    }
+
+    #[test]
+    fn test_gas_schedule_version() {
+        let params = TransactionGasParameters::initial();
+        assert_eq!(params.gas_schedule_version, LATEST_GAS_SCHEDULE_VERSION);
+        let mut gas_schedule: BTreeMap<String, u64> =
+            params.to_on_chain_gas_schedule().into_iter().collect();
+        let params = TransactionGasParameters::from_on_chain_gas_schedule(&gas_schedule).unwrap();
+        assert_eq!(params.gas_schedule_version, LATEST_GAS_SCHEDULE_VERSION);
+
+        // a legacy schedule without the entry.
+        gas_schedule.remove("txn.gas_schedule_version");
+        let params = TransactionGasParameters::from_on_chain_gas_schedule(&gas_schedule).unwrap();
+        assert_eq!(params.gas_schedule_version, 0);
+    }
+}
@@ -16,12 +16,14 @@ use move_core_types::{
 };
 use move_vm_types::gas::{GasMeter, SimpleInstruction};
 use move_vm_types::views::{TypeView, ValueView};
+use serde::{Deserialize, Serialize};
 use starcoin_gas_algebra_ext::{
-    FromOnChainGasSchedule, Gas, InitialGasSchedule, ToOnChainGasSchedule,
+    effective_fee, CostTable, FromOnChainGasSchedule, Gas, InitialGasSchedule, ToOnChainGasSchedule,
 };
 #[cfg(testing)]
 use starcoin_logger::prelude::*;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
 
 use move_binary_format::file_format_common::Opcodes;
 use starcoin_gas_algebra_ext::InstructionGasParameters;
@@ -51,6 +53,14 @@ impl FromOnChainGasSchedule for NativeGasParameters {
             table: FromOnChainGasSchedule::from_on_chain_gas_schedule(gas_schedule)?,
         })
     }
+
+    fn required_keys() -> Vec<String> {
+        let mut keys = move_stdlib::natives::GasParameters::required_keys();
+        keys.extend(move_stdlib::natives::NurseryGasParameters::required_keys());
+        keys.extend(starcoin_natives::GasParameters::required_keys());
+        keys.extend(move_table_extension::GasParameters::required_keys());
+        keys
+    }
 }
 
 impl ToOnChainGasSchedule for NativeGasParameters {
@@ -102,6 +112,13 @@ impl FromOnChainGasSchedule for StarcoinGasParameters {
             txn: FromOnChainGasSchedule::from_on_chain_gas_schedule(gas_schedule)?,
         })
     }
+
+    fn required_keys() -> Vec<String> {
+        let mut keys = InstructionGasParameters::required_keys();
+        keys.extend(TransactionGasParameters::required_keys());
+        keys.extend(NativeGasParameters::required_keys());
+        keys
+    }
 }
 
 impl ToOnChainGasSchedule for StarcoinGasParameters {
@@ -114,6 +131,11 @@ impl ToOnChainGasSchedule for StarcoinGasParameters {
 }
 
 impl StarcoinGasParameters {
+    /// The version of the loaded gas schedule, zero for a schedule without a version.
+    pub fn gas_schedule_version(&self) -> u64 {
+        self.txn.gas_schedule_version
+    }
+
     // Only used for genesis and for tests where we need a cost table and
     // don't have a genesis storage state.
     pub fn zeros() -> Self {
@@ -135,13 +157,216 @@ impl InitialGasSchedule for StarcoinGasParameters {
     }
 }
 
+/// The maximum number of charges kept by the trace of a `StarcoinGasMeter`, older entries are
+/// dropped first.
+pub const MAX_GAS_TRACE_ENTRIES: usize = 128;
+
+/// Where a gas charge comes from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GasChargeKind {
+    Intrinsic,
+    Instruction(Opcodes),
+    Native,
+    StorageWrite,
+}
+
+impl GasChargeKind {
+    fn name(&self) -> String {
+        match self {
+            GasChargeKind::Intrinsic => "INTRINSIC".to_string(),
+            GasChargeKind::Instruction(opcode) => format!("{:?}", opcode),
+            GasChargeKind::Native => "NATIVE_FUNCTION".to_string(),
+            GasChargeKind::StorageWrite => "STORAGE_WRITE".to_string(),
+        }
+    }
+}
+
+/// Gas charged by a `StarcoinGasMeter`, grouped by the kind of the charge, in internal gas units.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GasBreakdown {
+    pub intrinsic: u64,
+    pub instruction: u64,
+    pub native: u64,
+    pub storage_write: u64,
+}
+
+impl GasBreakdown {
+    fn add(&mut self, kind: GasChargeKind, amount: InternalGas) {
+        let bucket = match kind {
+            GasChargeKind::Intrinsic => &mut self.intrinsic,
+            GasChargeKind::Instruction(_) => &mut self.instruction,
+            GasChargeKind::Native => &mut self.native,
+            GasChargeKind::StorageWrite => &mut self.storage_write,
+        };
+        *bucket = bucket.saturating_add(amount.into());
+    }
+
+    pub fn total(&self) -> u64 {
+        self.intrinsic
+            .saturating_add(self.instruction)
+            .saturating_add(self.native)
+            .saturating_add(self.storage_write)
+    }
+
+    /// The per bucket change from `self` to `after`, e.g. the breakdown of the same transaction
+    /// under the current and a candidate gas schedule.
+    pub fn delta(&self, after: &GasBreakdown) -> GasBreakdownDelta {
+        let diff = |before: u64, after: u64| i128::from(after) - i128::from(before);
+        GasBreakdownDelta {
+            intrinsic: diff(self.intrinsic, after.intrinsic),
+            instruction: diff(self.instruction, after.instruction),
+            native: diff(self.native, after.native),
+            storage_write: diff(self.storage_write, after.storage_write),
+        }
+    }
+}
+
+/// One line of a `GasReport`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GasReportLine {
+    /// In internal gas units.
+    pub internal: u64,
+    /// In gas units, rounded up like the gas used of a transaction output.
+    pub gas: u64,
+}
+
+/// The gas cost of a transaction for end users, built from its `GasBreakdown`.
+/// The storage reads are charged by the `borrow_global` like instructions, so they are part of
+/// the instruction line.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GasReport {
+    pub intrinsic: GasReportLine,
+    pub instruction: GasReportLine,
+    pub native: GasReportLine,
+    pub storage_write: GasReportLine,
+    pub total: GasReportLine,
+    pub gas_price: u64,
+    /// The fee in NanoSTC, see `effective_fee`.
+    pub fee: u64,
+}
+
+impl GasReport {
+    pub fn new(cost_table: &CostTable, breakdown: &GasBreakdown, gas_price: u64) -> Self {
+        let scaling = std::cmp::max(cost_table.gas_constants.gas_unit_scaling_factor, 1);
+        let line = |internal: u64| GasReportLine {
+            internal,
+            gas: internal / scaling + u64::from(internal % scaling != 0),
+        };
+        let total = breakdown.total();
+        Self {
+            intrinsic: line(breakdown.intrinsic),
+            instruction: line(breakdown.instruction),
+            native: line(breakdown.native),
+            storage_write: line(breakdown.storage_write),
+            total: line(total),
+            gas_price,
+            fee: effective_fee(total.into(), gas_price.into(), scaling.into()),
+        }
+    }
+}
+
+impl fmt::Display for GasReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:<16}{:>16}{:>16}", "", "internal", "gas")?;
+        for (name, line) in [
+            ("intrinsic", self.intrinsic),
+            ("instruction", self.instruction),
+            ("native", self.native),
+            ("storage write", self.storage_write),
+            ("total", self.total),
+        ] {
+            writeln!(f, "{:<16}{:>16}{:>16}", name, line.internal, line.gas)?;
+        }
+        writeln!(f, "{:<16}{:>32}", "gas price", self.gas_price)?;
+        writeln!(f, "{:<16}{:>32}", "fee", self.fee)
+    }
+}
+
+/// The signed difference between two `GasBreakdown`s, in internal gas units.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GasBreakdownDelta {
+    pub intrinsic: i128,
+    pub instruction: i128,
+    pub native: i128,
+    pub storage_write: i128,
+}
+
+impl GasBreakdownDelta {
+    pub fn total(&self) -> i128 {
+        self.intrinsic + self.instruction + self.native + self.storage_write
+    }
+}
+
+/// A single charge recorded by the gas meter trace.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GasTraceEntry {
+    pub name: String,
+    /// The requested cost, in internal gas units.
+    pub cost: u64,
+    /// The balance after the charge, in internal gas units.
+    pub balance: u64,
+}
+
+/// A snapshot of the whole gas meter state, used for incident analysis when a transaction aborts.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GasMeterDump {
+    /// The remaining balance, in internal gas units.
+    pub balance: u64,
+    /// The total gas charged so far, in internal gas units.
+    pub total_charged: u64,
+    pub breakdown: GasBreakdown,
+    /// The last `MAX_GAS_TRACE_ENTRIES` charges, oldest first. Empty if the trace is not enabled.
+    pub trace: Vec<GasTraceEntry>,
+}
+
+/// Gas charged by a `StarcoinGasMeter` per bytecode instruction and per native function, in
+/// internal gas units. The intrinsic and storage write charges are not included, see
+/// `GasBreakdown` for them.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GasProfile {
+    /// Keyed by the opcode name, like `ADD`.
+    pub by_instruction: BTreeMap<String, InternalGas>,
+    /// Keyed by the module id, as printed by its `Display`, and the function name, like
+    /// `0x00000000000000000000000000000001::Vector::length`.
+    pub by_native: BTreeMap<String, InternalGas>,
+}
+
+struct GasProfiler {
+    profile: GasProfile,
+    /// The last called function, a native function is charged after its call.
+    last_call: String,
+}
+
+impl GasProfiler {
+    fn add(&mut self, kind: GasChargeKind, amount: InternalGas) {
+        let (bucket, name) = match kind {
+            GasChargeKind::Instruction(_) => (&mut self.profile.by_instruction, kind.name()),
+            GasChargeKind::Native => (&mut self.profile.by_native, self.last_call.clone()),
+            GasChargeKind::Intrinsic | GasChargeKind::StorageWrite => return,
+        };
+        let total = bucket.entry(name).or_insert_with(|| 0.into());
+        *total = *total + amount;
+    }
+}
+
 /// The official gas meter used inside the Starcoin VM.
 /// It maintains an internal gas counter, measured in internal gas units, and carries an environment
 /// consisting all the gas parameters, which it can lookup when performing gas calculations.
 pub struct StarcoinGasMeter {
     gas_params: StarcoinGasParameters,
     balance: InternalGas,
+    /// Gas set aside by `reserve`, not available until `release_reserve`.
+    reserved: InternalGas,
     charge: bool,
+    breakdown: GasBreakdown,
+    trace: Option<VecDeque<GasTraceEntry>>,
+    warning: Option<GasWarning>,
+    profiler: Option<GasProfiler>,
+}
+
+struct GasWarning {
+    threshold: InternalGas,
+    callback: Box<dyn Fn(InternalGas) + Send>,
 }
 
 impl StarcoinGasMeter {
@@ -150,7 +375,34 @@ impl StarcoinGasMeter {
         Self {
             gas_params,
             balance,
+            reserved: 0.into(),
             charge: true,
+            breakdown: GasBreakdown::default(),
+            trace: None,
+            warning: None,
+            profiler: None,
+        }
+    }
+
+    /// A gas meter which also records the `GasProfile` of the charges.
+    /// Without it the meter only pays a `None` check per charge.
+    pub fn with_profiling(gas_params: StarcoinGasParameters, balance: impl Into<Gas>) -> Self {
+        let mut gas_meter = Self::new(gas_params, balance);
+        gas_meter.profiler = Some(GasProfiler {
+            profile: GasProfile::default(),
+            last_call: String::new(),
+        });
+        gas_meter
+    }
+
+    /// The profile of the charges so far, `None` if the meter is not created by `with_profiling`.
+    pub fn profile(&self) -> Option<&GasProfile> {
+        self.profiler.as_ref().map(|profiler| &profiler.profile)
+    }
+
+    fn record_call(&mut self, module_id: &ModuleId, func_name: &str) {
+        if let Some(profiler) = self.profiler.as_mut() {
+            profiler.last_call = format!("{}::{}", module_id, func_name);
         }
     }
 
@@ -159,19 +411,111 @@ impl StarcoinGasMeter {
             .to_unit_round_down_with_params(&self.gas_params.txn)
     }
 
+    /// Deduct gas for the storage write of a transaction.
     pub fn deduct_gas(&mut self, amount: InternalGas) -> PartialVMResult<()> {
+        self.charge_gas(GasChargeKind::StorageWrite, amount)
+    }
+
+    /// Set aside `amount` of the balance, so it can not be consumed by the main execution.
+    /// The VM returns the reserved gas to the balance by `release_reserve` when it runs the
+    /// epilogue, which is unmetered and charges the fee from the balance.
+    pub fn reserve(&mut self, amount: InternalGas) -> PartialVMResult<()> {
+        match self.balance.checked_sub(amount) {
+            Some(new_balance) => {
+                self.balance = new_balance;
+                self.reserved = self.reserved + amount;
+                Ok(())
+            }
+            None => Err(PartialVMError::new(StatusCode::OUT_OF_GAS)),
+        }
+    }
+
+    /// Return the gas set aside by `reserve` to the balance.
+    pub fn release_reserve(&mut self) {
+        self.balance = self.balance + self.reserved;
+        self.reserved = 0.into();
+    }
+
+    pub fn reserved(&self) -> InternalGas {
+        self.reserved
+    }
+
+    fn charge_gas(&mut self, kind: GasChargeKind, amount: InternalGas) -> PartialVMResult<()> {
         if !self.charge {
             return Ok(());
         }
-        match self.balance.checked_sub(amount) {
+        let (charged, result) = match self.balance.checked_sub(amount) {
             Some(new_balance) => {
                 self.balance = new_balance;
-                Ok(())
+                (amount, Ok(()))
             }
             None => {
+                let charged = self.balance;
                 self.balance = 0.into();
-                Err(PartialVMError::new(StatusCode::OUT_OF_GAS))
+                (charged, Err(PartialVMError::new(StatusCode::OUT_OF_GAS)))
+            }
+        };
+        self.breakdown.add(kind, charged);
+        if let Some(profiler) = self.profiler.as_mut() {
+            profiler.add(kind, charged);
+        }
+        if self
+            .warning
+            .as_ref()
+            .map_or(false, |warning| self.balance < warning.threshold)
+        {
+            if let Some(warning) = self.warning.take() {
+                (warning.callback)(self.balance);
+            }
+        }
+        if let Some(trace) = self.trace.as_mut() {
+            if trace.len() == MAX_GAS_TRACE_ENTRIES {
+                trace.pop_front();
             }
+            trace.push_back(GasTraceEntry {
+                name: kind.name(),
+                cost: amount.into(),
+                balance: self.balance.into(),
+            });
+        }
+        result
+    }
+
+    /// Record the last `MAX_GAS_TRACE_ENTRIES` charges, so they are included in `dump`.
+    pub fn enable_trace(&mut self) {
+        if self.trace.is_none() {
+            self.trace = Some(VecDeque::with_capacity(MAX_GAS_TRACE_ENTRIES));
+        }
+    }
+
+    /// Call `f` with the remaining balance when it drops below `remaining` for the first time.
+    /// The callback fires at most once, setting a new threshold replaces the pending one.
+    pub fn set_warning_threshold(
+        &mut self,
+        remaining: InternalGas,
+        f: impl Fn(InternalGas) + Send + 'static,
+    ) {
+        self.warning = Some(GasWarning {
+            threshold: remaining,
+            callback: Box::new(f),
+        });
+    }
+
+    pub fn breakdown(&self) -> &GasBreakdown {
+        &self.breakdown
+    }
+
+    /// Dump the full meter state, the result can be serialized to json for logging.
+    pub fn dump(&self) -> GasMeterDump {
+        GasMeterDump {
+            balance: self.balance.into(),
+            total_charged: self.breakdown.total(),
+            breakdown: self.breakdown.clone(),
+            trace: self
+                .trace
+                .as_ref()
+                .map(|trace| trace.iter().cloned().collect())
+                .unwrap_or_default(),
         }
     }
 
@@ -190,7 +534,7 @@ impl StarcoinGasMeter {
             "charge_intrinsic_gas cost InternalGasUnits({}) {}",
             cost, self.charge
         );
-        self.deduct_gas(cost)
+        self.charge_gas(GasChargeKind::Intrinsic, cost)
             .map_err(|e| e.finish(Location::Undefined))
     }
 
@@ -220,7 +564,6 @@ fn cal_instr_with_byte(per_arg: InternalGasPerByte, size: NumBytes) -> InternalG
     per_arg * size
 }
 
-#[allow(dead_code)]
 #[inline]
 fn simple_instr_to_opcode(instr: SimpleInstruction) -> Opcodes {
     match instr {
@@ -297,7 +640,10 @@ impl GasMeter for StarcoinGasMeter {
             cost,
             self.charge
         );
-        self.deduct_gas(cost)
+        self.charge_gas(
+            GasChargeKind::Instruction(simple_instr_to_opcode(instr)),
+            cost,
+        )
     }
 
     fn charge_pop(&mut self, _popped_val: impl ValueView) -> PartialVMResult<()> {
@@ -308,17 +654,18 @@ impl GasMeter for StarcoinGasMeter {
             "simple_instr pop cost InternalGasUnits({}) {}",
             cost, self.charge
         );
-        self.deduct_gas(cost)
+        self.charge_gas(GasChargeKind::Instruction(Opcodes::POP), cost)
     }
 
     #[inline]
     fn charge_call(
         &mut self,
-        _module_id: &ModuleId,
-        _func_name: &str,
+        module_id: &ModuleId,
+        func_name: &str,
         args: impl ExactSizeIterator<Item = impl ValueView>,
         _num_locals: NumArgs,
     ) -> PartialVMResult<()> {
+        self.record_call(module_id, func_name);
         let params = &self.gas_params.instr;
         // Note args.len() may be zero, can't use args.len() + 1 directly
         let cost1 = cal_instr_with_arg(params.call_per_arg, NumArgs::new(1));
@@ -327,18 +674,19 @@ impl GasMeter for StarcoinGasMeter {
         let cost2 = cal_instr_with_arg(params.call_per_arg, NumArgs::new(args.len() as u64));
         #[cfg(testing)]
         info!("CALL cost InternalGasUnits({}) {}", cost2, self.charge);
-        self.deduct_gas(cost1 + cost2)
+        self.charge_gas(GasChargeKind::Instruction(Opcodes::CALL), cost1 + cost2)
     }
 
     #[inline]
     fn charge_call_generic(
         &mut self,
-        _module_id: &ModuleId,
-        _func_name: &str,
+        module_id: &ModuleId,
+        func_name: &str,
         ty_args: impl ExactSizeIterator<Item = impl TypeView>,
         args: impl ExactSizeIterator<Item = impl ValueView>,
         _num_locals: NumArgs,
     ) -> PartialVMResult<()> {
+        self.record_call(module_id, func_name);
         let params = &self.gas_params.instr;
         // Note args.len() may be zero, can't use ty_args.len() + args.len() + 1 directly
         let cost1 = cal_instr_with_arg(
@@ -357,7 +705,10 @@ impl GasMeter for StarcoinGasMeter {
             "CALL_GENERIC cost InternalGasUnits({}) {}",
             cost2, self.charge
         );
-        self.deduct_gas(cost1 + cost2)
+        self.charge_gas(
+            GasChargeKind::Instruction(Opcodes::CALL_GENERIC),
+            cost1 + cost2,
+        )
     }
 
     #[inline]
@@ -366,7 +717,7 @@ impl GasMeter for StarcoinGasMeter {
         let cost = cal_instr_with_byte(instr.ld_const_per_byte, size);
         #[cfg(testing)]
         info!("LD_CONST cost InternalGasUnits({}) {}", cost, self.charge);
-        self.deduct_gas(cost)
+        self.charge_gas(GasChargeKind::Instruction(Opcodes::LD_CONST), cost)
     }
 
     fn charge_ld_const_after_deserialization(
@@ -385,7 +736,7 @@ impl GasMeter for StarcoinGasMeter {
         );
         #[cfg(testing)]
         info!("COPY_LOC cost InternalGasUnits({}) {}", cost, self.charge);
-        self.deduct_gas(cost)
+        self.charge_gas(GasChargeKind::Instruction(Opcodes::COPY_LOC), cost)
     }
 
     #[inline]
@@ -397,7 +748,7 @@ impl GasMeter for StarcoinGasMeter {
         );
         #[cfg(testing)]
         info!("MOVE_LOC cost InternalGasUnits({}) {}", cost, self.charge);
-        self.deduct_gas(cost)
+        self.charge_gas(GasChargeKind::Instruction(Opcodes::MOVE_LOC), cost)
     }
 
     #[inline]
@@ -409,7 +760,7 @@ impl GasMeter for StarcoinGasMeter {
         );
         #[cfg(testing)]
         info!("ST_LOC cost InternalGasUnits({}) {}", cost, self.charge);
-        self.deduct_gas(cost)
+        self.charge_gas(GasChargeKind::Instruction(Opcodes::ST_LOC), cost)
     }
 
     #[inline]
@@ -423,9 +774,15 @@ impl GasMeter for StarcoinGasMeter {
         let size = args.fold(field_count, |acc, val| {
             acc + val.legacy_abstract_memory_size()
         });
-        let cost = match is_generic {
-            false => cal_instr_with_size(params.pack_per_abs_mem_unit, size),
-            true => cal_instr_with_size(params.pack_generic_per_abs_mem_unit, size),
+        let (cost, opcode) = match is_generic {
+            false => (
+                cal_instr_with_size(params.pack_per_abs_mem_unit, size),
+                Opcodes::PACK,
+            ),
+            true => (
+                cal_instr_with_size(params.pack_generic_per_abs_mem_unit, size),
+                Opcodes::PACK_GENERIC,
+            ),
         };
         #[cfg(testing)]
         {
@@ -438,7 +795,7 @@ impl GasMeter for StarcoinGasMeter {
                 info!("PACK cost InternalGasUnits({}) {}", cost, self.charge);
             }
         }
-        self.deduct_gas(cost)
+        self.charge_gas(GasChargeKind::Instruction(opcode), cost)
     }
 
     #[inline]
@@ -447,7 +804,6 @@ impl GasMeter for StarcoinGasMeter {
         is_generic: bool,
         args: impl ExactSizeIterator<Item = impl ValueView>,
     ) -> PartialVMResult<()> {
-        #[cfg(testing)]
         let opcode = {
             if is_generic {
                 Opcodes::UNPACK_GENERIC
@@ -477,7 +833,7 @@ impl GasMeter for StarcoinGasMeter {
             );
             cost += cost2;
         }
-        self.deduct_gas(cost)
+        self.charge_gas(GasChargeKind::Instruction(opcode), cost)
     }
 
     #[inline]
@@ -488,7 +844,7 @@ impl GasMeter for StarcoinGasMeter {
         );
         #[cfg(testing)]
         info!("READ_REF cost InternalGasUnits({}) {}", cost, self.charge);
-        self.deduct_gas(cost)
+        self.charge_gas(GasChargeKind::Instruction(Opcodes::READ_REF), cost)
     }
 
     #[inline]
@@ -503,7 +859,7 @@ impl GasMeter for StarcoinGasMeter {
         );
         #[cfg(testing)]
         info!("WRITE_REF cost InternalGasUnits({}) {}", cost, self.charge);
-        self.deduct_gas(cost)
+        self.charge_gas(GasChargeKind::Instruction(Opcodes::WRITE_REF), cost)
     }
 
     #[inline]
@@ -515,7 +871,7 @@ impl GasMeter for StarcoinGasMeter {
         );
         #[cfg(testing)]
         info!("EQ cost InternalGasUnits({}) {}", cost, self.charge);
-        self.deduct_gas(cost)
+        self.charge_gas(GasChargeKind::Instruction(Opcodes::EQ), cost)
     }
 
     #[inline]
@@ -527,7 +883,7 @@ impl GasMeter for StarcoinGasMeter {
         );
         #[cfg(testing)]
         info!("NEQ cost InternalGasUnits({}) {}", cost, self.charge);
-        self.deduct_gas(cost)
+        self.charge_gas(GasChargeKind::Instruction(Opcodes::NEQ), cost)
     }
 
     #[inline]
@@ -549,7 +905,6 @@ impl GasMeter for StarcoinGasMeter {
             };
             cal_instr_with_size(param, REFERENCE_SIZE)
         };
-        #[cfg(testing)]
         let opcode = match is_generic {
             false => Opcodes::MUT_BORROW_GLOBAL,
             true => Opcodes::MUT_BORROW_GLOBAL_GENERIC,
@@ -559,7 +914,7 @@ impl GasMeter for StarcoinGasMeter {
             "{:#?} cost InternalGasUnits({}) {}",
             opcode, cost, self.charge
         );
-        self.deduct_gas(cost)
+        self.charge_gas(GasChargeKind::Instruction(opcode), cost)
     }
 
     #[inline]
@@ -579,7 +934,6 @@ impl GasMeter for StarcoinGasMeter {
             true => REFERENCE_SIZE,
         };
         let cost = cal_instr_with_size(param, size);
-        #[cfg(testing)]
         let opcode = match is_generic {
             false => Opcodes::EXISTS,
             true => Opcodes::EXISTS_GENERIC,
@@ -589,7 +943,7 @@ impl GasMeter for StarcoinGasMeter {
             "{:#?} cost InternalGasUnits({}) {}",
             opcode, cost, self.charge
         );
-        self.deduct_gas(cost)
+        self.charge_gas(GasChargeKind::Instruction(opcode), cost)
     }
 
     #[inline]
@@ -606,7 +960,6 @@ impl GasMeter for StarcoinGasMeter {
                 true => params.move_from_generic_per_abs_mem_unit,
             };
             let cost = cal_instr_with_size(param, val.legacy_abstract_memory_size());
-            #[cfg(testing)]
             let opcode = match is_generic {
                 false => Opcodes::MOVE_FROM,
                 true => Opcodes::MOVE_FROM_GENERIC,
@@ -616,7 +969,7 @@ impl GasMeter for StarcoinGasMeter {
                 "MOVE_FROM {:#?} cost InternalGasUnits({}) {}",
                 opcode, cost, self.charge
             );
-            return self.deduct_gas(cost);
+            return self.charge_gas(GasChargeKind::Instruction(opcode), cost);
         }
         Ok(())
     }
@@ -639,7 +992,6 @@ impl GasMeter for StarcoinGasMeter {
             };
             cal_instr_with_size(param, val.legacy_abstract_memory_size())
         };
-        #[cfg(testing)]
         let opcode = match is_generic {
             false => Opcodes::MOVE_TO,
             true => Opcodes::MOVE_TO_GENERIC,
@@ -649,7 +1001,7 @@ impl GasMeter for StarcoinGasMeter {
             "charge_MOVE_TO {:#?} cost InternalGasUnits({}) {}",
             opcode, cost, self.charge
         );
-        self.deduct_gas(cost)
+        self.charge_gas(GasChargeKind::Instruction(opcode), cost)
     }
 
     #[inline]
@@ -663,7 +1015,7 @@ impl GasMeter for StarcoinGasMeter {
         let cost = cal_instr_with_arg(params.vec_pack_per_elem, num_args);
         #[cfg(testing)]
         info!("VEC_PACK cost InternalGasUnits({}) {}", cost, self.charge);
-        self.deduct_gas(cost)
+        self.charge_gas(GasChargeKind::Instruction(Opcodes::VEC_PACK), cost)
     }
 
     #[inline]
@@ -671,7 +1023,7 @@ impl GasMeter for StarcoinGasMeter {
         let cost = self.gas_params.instr.vec_len_base;
         #[cfg(testing)]
         info!("VEC_LEN cost InternalGasUnits({}) {}", cost, self.charge);
-        self.deduct_gas(cost)
+        self.charge_gas(GasChargeKind::Instruction(Opcodes::VEC_LEN), cost)
     }
 
     #[inline]
@@ -690,17 +1042,16 @@ impl GasMeter for StarcoinGasMeter {
                 true => params.vec_mut_borrow_base,
             }
         };
-        #[cfg(testing)]
         let opcode = match is_mut {
-            false => Opcodes::VEC_MUT_BORROW,
-            true => Opcodes::VEC_IMM_BORROW,
+            false => Opcodes::VEC_IMM_BORROW,
+            true => Opcodes::VEC_MUT_BORROW,
         };
         #[cfg(testing)]
         info!(
             "{:#?} cost InternalGasUnits({}) {}",
             opcode, cost, self.charge
         );
-        self.deduct_gas(cost)
+        self.charge_gas(GasChargeKind::Instruction(opcode), cost)
     }
 
     #[inline]
@@ -718,7 +1069,7 @@ impl GasMeter for StarcoinGasMeter {
             "VEC_PUSH_BACK cost InternalGasUnits({}) {}",
             cost, self.charge
         );
-        self.deduct_gas(cost)
+        self.charge_gas(GasChargeKind::Instruction(Opcodes::VEC_PUSH_BACK), cost)
     }
 
     #[inline]
@@ -733,7 +1084,7 @@ impl GasMeter for StarcoinGasMeter {
             "VEC_POP_BACK cost InternalGasUnits({}) {}",
             cost, self.charge
         );
-        self.deduct_gas(cost)
+        self.charge_gas(GasChargeKind::Instruction(Opcodes::VEC_POP_BACK), cost)
     }
 
     #[inline]
@@ -749,7 +1100,7 @@ impl GasMeter for StarcoinGasMeter {
         );
         #[cfg(testing)]
         info!("VEC_UNPACK cost InternalGasUnits({}) {}", cost, self.charge);
-        self.deduct_gas(cost)
+        self.charge_gas(GasChargeKind::Instruction(Opcodes::VEC_UNPACK), cost)
     }
 
     #[inline]
@@ -757,7 +1108,7 @@ impl GasMeter for StarcoinGasMeter {
         let cost = self.gas_params.instr.vec_swap_base;
         #[cfg(testing)]
         info!("VEC_SWAP cost InternalGasUnits({}) {}", cost, self.charge);
-        self.deduct_gas(cost)
+        self.charge_gas(GasChargeKind::Instruction(Opcodes::VEC_SWAP), cost)
     }
 
     #[inline]
@@ -779,7 +1130,7 @@ impl GasMeter for StarcoinGasMeter {
             "NATIVE_FUNCTION cost InternalGasUnits({}) {}",
             amount, self.charge
         );
-        self.deduct_gas(amount)
+        self.charge_gas(GasChargeKind::Native, amount)
     }
 
     fn charge_native_function_before_execution(
@@ -797,3 +1148,279 @@ impl GasMeter for StarcoinGasMeter {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use move_core_types::account_address::AccountAddress;
+    use move_core_types::identifier::Identifier;
+    use move_vm_types::values::Value;
+    use starcoin_gas_algebra_ext::GasConstants;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn test_dump_after_out_of_gas() {
+        let mut gas_meter = StarcoinGasMeter::new(StarcoinGasParameters::initial(), 1000);
+        gas_meter.enable_trace();
+        gas_meter
+            .charge_intrinsic_gas_for_transaction(NumBytes::new(0))
+            .unwrap();
+        let mut steps = 0;
+        while gas_meter
+            .charge_simple_instr(SimpleInstruction::Add)
+            .is_ok()
+        {
+            steps += 1;
+        }
+        assert!(steps > 0);
+
+        let dump = gas_meter.dump();
+        assert_eq!(dump.balance, 0);
+        assert_eq!(dump.total_charged, 1000);
+        assert_eq!(dump.breakdown.total(), dump.total_charged);
+        assert!(dump.breakdown.intrinsic > 0);
+        assert!(dump.breakdown.instruction > 0);
+        assert_eq!(dump.trace.len(), MAX_GAS_TRACE_ENTRIES);
+        let last = dump.trace.last().unwrap();
+        assert_eq!(last.name, "ADD");
+        assert_eq!(last.balance, 0);
+
+        let json = serde_json::to_string(&dump).unwrap();
+        let decoded: GasMeterDump = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, dump);
+    }
+
+    #[test]
+    fn test_dump_without_trace() {
+        let mut gas_meter = StarcoinGasMeter::new(StarcoinGasParameters::initial(), 1000);
+        gas_meter
+            .charge_simple_instr(SimpleInstruction::Add)
+            .unwrap();
+        let dump = gas_meter.dump();
+        assert!(dump.trace.is_empty());
+        assert_eq!(dump.balance + dump.total_charged, 1000);
+    }
+
+    #[test]
+    fn test_warning_threshold() {
+        let mut gas_meter = StarcoinGasMeter::new(StarcoinGasParameters::initial(), 1000);
+        let warnings = Arc::new(Mutex::new(vec![]));
+        let recorded = warnings.clone();
+        gas_meter.set_warning_threshold(InternalGas::new(500), move |remaining| {
+            recorded.lock().unwrap().push(remaining)
+        });
+        while gas_meter
+            .charge_simple_instr(SimpleInstruction::Add)
+            .is_ok()
+        {}
+
+        let warnings = warnings.lock().unwrap();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0] < InternalGas::new(500));
+    }
+
+    #[test]
+    fn test_breakdown_delta() {
+        let run = |gas_params: StarcoinGasParameters| {
+            let mut gas_meter = StarcoinGasMeter::new(gas_params, 1_000_000);
+            gas_meter
+                .charge_intrinsic_gas_for_transaction(NumBytes::new(0))
+                .unwrap();
+            gas_meter
+                .charge_simple_instr(SimpleInstruction::Add)
+                .unwrap();
+            gas_meter.deduct_gas(gas_meter.cal_write_set_gas()).unwrap();
+            gas_meter.breakdown().clone()
+        };
+        let before = StarcoinGasParameters::initial();
+        let mut after = before.clone();
+        after.txn.global_memory_per_byte_write_cost =
+            (u64::from(before.txn.global_memory_per_byte_write_cost) * 2).into();
+
+        let before = run(before);
+        let after = run(after);
+        let delta = before.delta(&after);
+        assert_eq!(delta.intrinsic, 0);
+        assert_eq!(delta.instruction, 0);
+        assert_eq!(delta.native, 0);
+        assert_eq!(delta.storage_write, i128::from(before.storage_write));
+        assert_eq!(
+            delta.total(),
+            i128::from(after.total()) - i128::from(before.total())
+        );
+    }
+
+    #[test]
+    fn test_reserve() {
+        let mut gas_meter = StarcoinGasMeter::new(StarcoinGasParameters::initial(), 1000);
+        assert!(gas_meter.reserve(InternalGas::new(1001)).is_err());
+        gas_meter.reserve(InternalGas::new(300)).unwrap();
+        assert_eq!(gas_meter.reserved(), InternalGas::new(300));
+        assert_eq!(gas_meter.balance_internal(), InternalGas::new(700));
+
+        // the main execution runs out of the non-reserved gas.
+        while gas_meter
+            .charge_simple_instr(SimpleInstruction::Add)
+            .is_ok()
+        {}
+        assert_eq!(gas_meter.balance_internal(), InternalGas::new(0));
+        assert_eq!(gas_meter.reserved(), InternalGas::new(300));
+
+        // the epilogue runs from the reserve.
+        gas_meter.release_reserve();
+        assert_eq!(gas_meter.reserved(), InternalGas::new(0));
+        gas_meter.deduct_gas(InternalGas::new(300)).unwrap();
+        assert_eq!(gas_meter.dump().total_charged, 1000);
+    }
+
+    #[test]
+    fn test_from_on_chain_gas_schedule_validate() {
+        let mut gas_schedule: BTreeMap<String, u64> = StarcoinGasParameters::initial()
+            .to_on_chain_gas_schedule()
+            .into_iter()
+            .collect();
+        let params = StarcoinGasParameters::from_on_chain_gas_schedule(&gas_schedule).unwrap();
+        assert!(GasConstants::from(&params.txn).validate().is_ok());
+
+        // an invalid schedule is still loaded as it is, the validation is only reported.
+        gas_schedule.insert("txn.min_price_per_gas_unit".to_string(), 10_001);
+        let params = StarcoinGasParameters::from_on_chain_gas_schedule(&gas_schedule).unwrap();
+        assert_eq!(u64::from(params.txn.min_price_per_gas_unit), 10_001);
+        assert!(GasConstants::from(&params.txn).validate().is_err());
+    }
+
+    #[test]
+    fn test_gas_profile() {
+        let mut gas_meter = StarcoinGasMeter::new(StarcoinGasParameters::initial(), 1000);
+        gas_meter
+            .charge_simple_instr(SimpleInstruction::Add)
+            .unwrap();
+        assert!(gas_meter.profile().is_none());
+
+        let mut gas_meter =
+            StarcoinGasMeter::with_profiling(StarcoinGasParameters::initial(), 1_000_000);
+        gas_meter
+            .charge_intrinsic_gas_for_transaction(NumBytes::new(0))
+            .unwrap();
+        for _ in 0..3 {
+            gas_meter
+                .charge_simple_instr(SimpleInstruction::Add)
+                .unwrap();
+        }
+        gas_meter
+            .charge_simple_instr(SimpleInstruction::LdU64)
+            .unwrap();
+        gas_meter
+            .charge_simple_instr(SimpleInstruction::Ret)
+            .unwrap();
+
+        let add = gas_meter
+            .gas_params
+            .instr
+            .simple_instr_cost(SimpleInstruction::Add)
+            .unwrap();
+        let profile = gas_meter.profile().unwrap();
+        assert_eq!(profile.by_instruction.len(), 3);
+        assert_eq!(profile.by_instruction["ADD"], add + add + add);
+        assert!(profile.by_native.is_empty());
+        let total = profile
+            .by_instruction
+            .values()
+            .fold(InternalGas::new(0), |total, gas| total + *gas);
+        assert_eq!(u64::from(total), gas_meter.breakdown().instruction);
+        assert_eq!(
+            gas_meter.breakdown().total(),
+            gas_meter.breakdown().intrinsic + u64::from(total)
+        );
+    }
+
+    #[test]
+    fn test_gas_profile_native() {
+        let mut gas_meter =
+            StarcoinGasMeter::with_profiling(StarcoinGasParameters::initial(), 1_000_000);
+        let module_id = ModuleId::new(AccountAddress::ONE, Identifier::new("Vector").unwrap());
+        gas_meter
+            .charge_call(
+                &module_id,
+                "length",
+                std::iter::empty::<Value>(),
+                NumArgs::new(0),
+            )
+            .unwrap();
+        gas_meter
+            .charge_native_function(InternalGas::new(98), None::<std::iter::Empty<Value>>)
+            .unwrap();
+        gas_meter
+            .charge_native_function(InternalGas::new(2), None::<std::iter::Empty<Value>>)
+            .unwrap();
+
+        let profile = gas_meter.profile().unwrap();
+        assert_eq!(profile.by_native.len(), 1);
+        assert_eq!(
+            profile.by_native["0x00000000000000000000000000000001::Vector::length"],
+            InternalGas::new(100)
+        );
+        // the call itself is an instruction.
+        assert!(profile.by_instruction.contains_key("CALL"));
+        assert_eq!(
+            u64::from(
+                profile
+                    .by_native
+                    .values()
+                    .fold(InternalGas::new(0), |total, gas| total + *gas)
+            ),
+            gas_meter.breakdown().native
+        );
+    }
+
+    #[test]
+    fn test_gas_report() {
+        let mut cost_table = CostTable {
+            instruction_table: vec![],
+            native_table: vec![],
+            gas_constants: GasConstants {
+                global_memory_per_byte_cost: 4,
+                global_memory_per_byte_write_cost: 9,
+                min_transaction_gas_units: 600,
+                large_transaction_cutoff: 600,
+                intrinsic_gas_per_byte: 8,
+                maximum_number_of_gas_units: 40_000_000,
+                min_price_per_gas_unit: 1,
+                max_price_per_gas_unit: 10_000,
+                max_transaction_size_in_bytes: 128 * 1024,
+                gas_unit_scaling_factor: 1,
+                default_account_size: 800,
+            },
+        };
+        let breakdown = GasBreakdown {
+            intrinsic: 600,
+            instruction: 1234,
+            native: 56,
+            storage_write: 7200,
+        };
+        let report = GasReport::new(&cost_table, &breakdown, 2);
+        assert_eq!(report.intrinsic.internal, 600);
+        assert_eq!(report.instruction.internal, 1234);
+        assert_eq!(report.native.internal, 56);
+        assert_eq!(report.storage_write.internal, 7200);
+        assert_eq!(report.total.internal, 9090);
+        assert_eq!(report.total.gas, 9090);
+        assert_eq!(report.fee, 18180);
+        assert_eq!(
+            report.intrinsic.internal
+                + report.instruction.internal
+                + report.native.internal
+                + report.storage_write.internal,
+            report.total.internal
+        );
+        let text = report.to_string();
+        assert_eq!(text.lines().count(), 8);
+        assert!(text.contains("fee"));
+
+        cost_table.gas_constants.gas_unit_scaling_factor = 100;
+        let report = GasReport::new(&cost_table, &breakdown, 2);
+        assert_eq!(report.instruction.gas, 13);
+        assert_eq!(report.total.gas, 91);
+        assert_eq!(report.fee, 182);
+    }
+}
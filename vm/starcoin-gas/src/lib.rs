@@ -20,7 +20,10 @@
 
 mod gas_meter;
 
-pub use gas_meter::{NativeGasParameters, StarcoinGasMeter, StarcoinGasParameters};
+pub use gas_meter::{
+    GasBreakdown, GasBreakdownDelta, GasChargeKind, GasMeterDump, GasTraceEntry,
+    NativeGasParameters, StarcoinGasMeter, StarcoinGasParameters, MAX_GAS_TRACE_ENTRIES,
+};
 pub use move_core_types::gas_algebra::{
     Arg, Byte, GasQuantity, InternalGas, InternalGasPerArg, InternalGasPerByte, InternalGasUnit,
     NumArgs, NumBytes, UnitDiv,
@@ -1,5 +1,7 @@
 use once_cell::sync::Lazy;
-use starcoin_gas_algebra_ext::{CostTable, GasConstants};
+use starcoin_gas_algebra_ext::GasConstants;
+pub use starcoin_gas_algebra_ext::{CostTable, GasScheduleChange, GasScheduleDiff};
+use std::fmt;
 
 #[allow(non_camel_case_types)]
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
@@ -142,3 +144,44 @@ pub fn latest_cost_table(gas_constants: GasConstants) -> CostTable {
 /// only used in starcoin vm when init genesis
 pub static G_LATEST_GAS_SCHEDULE: Lazy<CostTable> =
     Lazy::new(|| latest_cost_table(G_LATEST_GAS_CONSTANTS.clone()));
+
+/// Named gas schedules for setting up networks and tests.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GasPreset {
+    /// The dev schedule without storage costs, for fast tests.
+    Test,
+    /// The schedule of the test and dev network genesis.
+    Dev,
+    /// The production schedule, see `G_LATEST_GAS_SCHEDULE`.
+    Main,
+}
+
+impl GasPreset {
+    pub const ALL: [GasPreset; 3] = [GasPreset::Test, GasPreset::Dev, GasPreset::Main];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            GasPreset::Test => "test",
+            GasPreset::Dev => "dev",
+            GasPreset::Main => "main",
+        }
+    }
+
+    pub fn cost_table(&self) -> CostTable {
+        match self {
+            GasPreset::Test => latest_cost_table(GasConstants {
+                global_memory_per_byte_cost: 0,
+                global_memory_per_byte_write_cost: 0,
+                ..G_TEST_GAS_CONSTANTS.clone()
+            }),
+            GasPreset::Dev => latest_cost_table(G_TEST_GAS_CONSTANTS.clone()),
+            GasPreset::Main => G_LATEST_GAS_SCHEDULE.clone(),
+        }
+    }
+}
+
+impl fmt::Display for GasPreset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
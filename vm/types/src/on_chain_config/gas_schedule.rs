@@ -1,8 +1,9 @@
 use crate::gas_schedule::{
-    G_MAX_TRANSACTION_SIZE_IN_BYTES_V1, G_MAX_TRANSACTION_SIZE_IN_BYTES_V2,
+    CostTable, G_MAX_TRANSACTION_SIZE_IN_BYTES_V1, G_MAX_TRANSACTION_SIZE_IN_BYTES_V2,
     G_MAX_TRANSACTION_SIZE_IN_BYTES_V3,
 };
-use crate::on_chain_config::{OnChainConfig, VMConfig};
+use crate::on_chain_config::{instruction_table_v1, instruction_table_v2, OnChainConfig, VMConfig};
+use anyhow::{bail, ensure, format_err, Result};
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
@@ -17,6 +18,17 @@ impl GasSchedule {
         // TODO: what if the gas schedule contains duplicated entries?
         self.entries.into_iter().collect()
     }
+
+    /// Render the schedule as `name = value` lines sorted by name, one entry per line,
+    /// so the diff of two schedules only shows the changed entries.
+    pub fn to_review_text(&self) -> String {
+        let mut entries: Vec<&(String, u64)> = self.entries.iter().collect();
+        entries.sort();
+        entries
+            .into_iter()
+            .map(|(name, value)| format!("{} = {}\n", name, value))
+            .collect()
+    }
 }
 
 // instruction_table_v1
@@ -582,6 +594,87 @@ static G_NATIVE_STRS: Lazy<Vec<&str>> = Lazy::new(|| {
     ]
 });
 
+/// Check the native table of `cost_table` has a cost entry for every native of the latest
+/// native gas schedule, and no entry without a native.
+pub fn validate_native_coverage(cost_table: &CostTable) -> Result<()> {
+    let (_, _, natives) = G_VM_VERSION_EXPECTATIONS
+        .last()
+        .expect("vm version expectations must not be empty");
+    check_native_coverage(cost_table.native_table.len(), natives)
+}
+
+/// The expected instruction_table length and native gas schedule of each VM version.
+static G_VM_VERSION_EXPECTATIONS: Lazy<Vec<(u32, usize, Vec<(String, u64)>)>> = Lazy::new(|| {
+    vec![
+        (1, instruction_table_v1().len(), native_gas_schedule_v1()),
+        (2, instruction_table_v1().len(), native_gas_schedule_v2()),
+        (3, instruction_table_v2().len(), native_gas_schedule_v3()),
+        (4, instruction_table_v2().len(), native_gas_schedule_v4()),
+    ]
+});
+
+/// Check `cost_table` has the table dimensions the VM of `vm_version` expects,
+/// and covers every native of that version.
+pub fn check_compatibility(cost_table: &CostTable, vm_version: u32) -> Result<()> {
+    let (_, instruction_len, natives) = G_VM_VERSION_EXPECTATIONS
+        .iter()
+        .find(|(version, _, _)| *version == vm_version)
+        .ok_or_else(|| format_err!("Unknown vm version {}", vm_version))?;
+    ensure!(
+        cost_table.instruction_table.len() == *instruction_len,
+        "vm version {} expects {} instruction_table entries, but got {}",
+        vm_version,
+        instruction_len,
+        cost_table.instruction_table.len()
+    );
+    check_native_coverage(cost_table.native_table.len(), natives)
+}
+
+/// The names of the natives of a native table with `native_table_len` entries, by position.
+/// The deprecated natives have no name and are skipped, as in `GasSchedule::from`.
+fn native_table_names(native_table_len: usize) -> Result<Vec<&'static str>> {
+    let natives = G_NATIVE_STRS.get(..native_table_len).ok_or_else(|| {
+        format_err!(
+            "native_table has {} entries, but only {} natives are registered",
+            native_table_len,
+            G_NATIVE_STRS.len()
+        )
+    })?;
+    Ok(natives
+        .iter()
+        .copied()
+        .filter(|name| !name.is_empty())
+        .collect())
+}
+
+/// Check the natives of the native table match the `expected` native gas schedule entry by entry.
+fn check_native_coverage(native_table_len: usize, expected: &[(String, u64)]) -> Result<()> {
+    let names = native_table_names(native_table_len)?;
+    for (idx, (name, _)) in expected.iter().enumerate() {
+        match names.get(idx) {
+            Some(actual) => ensure!(
+                *actual == name.as_str(),
+                "Native {:?} (entry {}) is registered as {:?} in native_table",
+                name,
+                idx,
+                actual
+            ),
+            None => bail!(
+                "Native {:?} (entry {}) has no cost entry in native_table",
+                name,
+                idx
+            ),
+        }
+    }
+    ensure!(
+        names.len() == expected.len(),
+        "native_table has {} entries, but only {} natives are expected",
+        names.len(),
+        expected.len()
+    );
+    Ok(())
+}
+
 // https://github.com/starcoinorg/starcoin-framework/blob/main/sources/VMConfig.move
 impl From<&VMConfig> for GasSchedule {
     fn from(vm_config: &VMConfig) -> Self {
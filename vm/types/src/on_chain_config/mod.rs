@@ -28,14 +28,14 @@ pub use self::{
     consensus_config::{consensus_config_type_tag, ConsensusConfig, G_CONSENSUS_CONFIG_IDENTIFIER},
     dao_config::DaoConfig,
     gas_schedule::{
-        instruction_gas_schedule_v1, instruction_gas_schedule_v2, native_gas_schedule_v1,
-        native_gas_schedule_v2, native_gas_schedule_v3, native_gas_schedule_v4,
-        txn_gas_schedule_test, txn_gas_schedule_v1, txn_gas_schedule_v2, txn_gas_schedule_v3,
-        GasSchedule,
+        check_compatibility, instruction_gas_schedule_v1, instruction_gas_schedule_v2,
+        native_gas_schedule_v1, native_gas_schedule_v2, native_gas_schedule_v3,
+        native_gas_schedule_v4, txn_gas_schedule_test, txn_gas_schedule_v1, txn_gas_schedule_v2,
+        txn_gas_schedule_v3, validate_native_coverage, GasSchedule,
     },
     genesis_gas_schedule::{
         instruction_table_v1, instruction_table_v2, native_table_v1, native_table_v2,
-        G_LATEST_INSTRUCTION_TABLE, G_LATEST_NATIVE_TABLE,
+        v3_native_table, v4_native_table, G_LATEST_INSTRUCTION_TABLE, G_LATEST_NATIVE_TABLE,
     },
     move_lang_version::MoveLanguageVersion,
     version::{version_config_type_tag, Version, G_VERSION_CONFIG_IDENTIFIER},
@@ -98,6 +98,11 @@ impl Epoch {
         self.block_gas_limit
     }
 
+    /// The gas units left for packing transactions into a block which has already used `used`.
+    pub fn remaining_block_gas(&self, used: u64) -> u64 {
+        self.block_gas_limit.saturating_sub(used)
+    }
+
     pub fn strategy(&self) -> ConsensusStrategy {
         ConsensusStrategy::try_from(self.strategy).expect("epoch consensus strategy must exist.")
     }
@@ -229,3 +234,34 @@ impl EpochData {
         AccessPath::resource_data_path(EpochData::struct_tag_for_epoch())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn epoch(block_gas_limit: u64) -> Epoch {
+        Epoch::new(
+            0,
+            0,
+            0,
+            100,
+            1000,
+            0,
+            0,
+            24,
+            2,
+            block_gas_limit,
+            0,
+            EventHandle::random_handle(0),
+        )
+    }
+
+    #[test]
+    fn test_remaining_block_gas() {
+        let epoch = epoch(50_000_000);
+        assert_eq!(epoch.remaining_block_gas(0), 50_000_000);
+        assert_eq!(epoch.remaining_block_gas(10_000_000), 40_000_000);
+        assert_eq!(epoch.remaining_block_gas(50_000_000), 0);
+        assert_eq!(epoch.remaining_block_gas(50_000_001), 0);
+    }
+}
@@ -10,7 +10,7 @@
 
 use crate::state_store::state_key::StateKey;
 use crate::{
-    access_path::AccessPath,
+    access_path::{AccessPath, DataPath},
     account_config::{
         genesis_address, token_code::TokenCode, AccountResource, BalanceResource, TokenInfo,
         G_STC_TOKEN_CODE,
@@ -24,12 +24,18 @@ use crate::{
     },
     sips::SIP,
 };
-use anyhow::{format_err, Result};
+use anyhow::{bail, format_err, Result};
 use move_core_types::{
     account_address::AccountAddress,
-    language_storage::{ModuleId, StructTag},
+    identifier,
+    language_storage::{ModuleId, StructTag, TypeTag},
 };
 use serde::de::DeserializeOwned;
+use starcoin_gas_algebra_ext::GasConstants;
+use std::any::{Any, TypeId};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard};
 
 /// `StateView` is a trait that defines a read-only snapshot of the global state. It is passed to
 /// the VM for transaction execution, during which the VM is guaranteed to read anything at the
@@ -38,9 +44,97 @@ pub trait StateView {
     /// Gets the state value for a given state key.
     fn get_state_value(&self, state_key: &StateKey) -> Result<Option<Vec<u8>>>;
 
+    /// Gets the state values for the given state keys, in the order of the keys, `None` for the
+    /// missing ones. Views over a backend which can batch the reads should override it.
+    fn multi_get_state_value(&self, state_keys: &[StateKey]) -> Result<Vec<Option<Vec<u8>>>> {
+        state_keys
+            .iter()
+            .map(|state_key| self.get_state_value(state_key))
+            .collect()
+    }
+
     /// VM needs this method to know whether the current state view is for genesis state creation.
     /// Currently TransactionPayload::WriteSet is only valid for genesis state creation.
     fn is_genesis(&self) -> bool;
+
+    /// Iterate the addresses of all the accounts in the state, streaming from the backing store.
+    /// Only tree-backed views support this, the others return an error.
+    fn iter_accounts(&self) -> Result<Box<dyn Iterator<Item = Result<AccountAddress>> + '_>> {
+        bail!("Iterate accounts is not supported by this state view")
+    }
+}
+
+/// A `StateView` which can scan its state entries by access path prefix.
+pub trait StateViewScan: StateView {
+    /// Iterate the access paths which start with `prefix`, with their values, see
+    /// `access_path_has_prefix`. The order is defined by the implementor, the in-memory view
+    /// returns them in the order of the access paths, the tree-backed views in the order of
+    /// their key hash. Views without ordered iteration return an error.
+    fn iter_prefix(
+        &self,
+        _prefix: &AccessPath,
+    ) -> Result<Box<dyn Iterator<Item = Result<(AccessPath, Vec<u8>)>> + '_>> {
+        bail!("Iterate prefix is not supported by this state view")
+    }
+}
+
+/// Check the access path has the address and data type of `prefix`, and its path starts with the
+/// path of `prefix` in text form, so a struct without type parameters matches all its instances.
+pub fn access_path_has_prefix(access_path: &AccessPath, prefix: &AccessPath) -> bool {
+    access_path.address == prefix.address
+        && access_path.path.data_type() == prefix.path.data_type()
+        && access_path
+            .path
+            .to_string()
+            .starts_with(prefix.path.to_string().as_str())
+}
+
+/// The max nesting depth of the type parameters in a resource access path.
+pub const MAX_ACCESS_PATH_TYPE_DEPTH: usize = 16;
+
+/// Check the access path is structurally valid, so an encoding bug is reported as an error
+/// instead of masquerading as missing data. The reads do not check it, the access paths built
+/// by the node are valid, so call it where an access path comes from outside, e.g. the RPC.
+pub fn validate_access_path(path: &AccessPath) -> Result<()> {
+    match &path.path {
+        DataPath::Code(module_name) => validate_identifier(path, module_name.as_str()),
+        DataPath::Resource(struct_tag) => validate_struct_tag(path, struct_tag, 0),
+    }
+}
+
+fn validate_identifier(path: &AccessPath, ident: &str) -> Result<()> {
+    if !identifier::is_valid(ident) {
+        bail!(
+            "Invalid access path {}: invalid identifier {:?}",
+            path,
+            ident
+        );
+    }
+    Ok(())
+}
+
+fn validate_struct_tag(path: &AccessPath, struct_tag: &StructTag, depth: usize) -> Result<()> {
+    validate_identifier(path, struct_tag.module.as_str())?;
+    validate_identifier(path, struct_tag.name.as_str())?;
+    for type_param in &struct_tag.type_params {
+        validate_type_tag(path, type_param, depth + 1)?;
+    }
+    Ok(())
+}
+
+fn validate_type_tag(path: &AccessPath, type_tag: &TypeTag, depth: usize) -> Result<()> {
+    if depth > MAX_ACCESS_PATH_TYPE_DEPTH {
+        bail!(
+            "Invalid access path {}: type parameters nested deeper than {}",
+            path,
+            MAX_ACCESS_PATH_TYPE_DEPTH
+        );
+    }
+    match type_tag {
+        TypeTag::Struct(struct_tag) => validate_struct_tag(path, struct_tag, depth),
+        TypeTag::Vector(element) => validate_type_tag(path, element, depth + 1),
+        _ => Ok(()),
+    }
 }
 
 impl<T: ?Sized> StateReaderExt for T where T: StateView {}
@@ -196,3 +290,545 @@ pub trait StateReaderExt: StateView {
         self.get_proposal(G_STC_TOKEN_CODE.clone())
     }
 }
+
+type CachedResource = Option<Box<dyn Any + Send>>;
+
+/// A `StateView` wrapper which memoizes the typed resources read by `ResourceCache::get_resource`,
+/// so repeated reads of the same resource skip the inner view and the deserialization.
+/// The cache is only valid for the state root of the inner view, call `invalidate` or build a
+/// new cache when the state root changes.
+pub struct ResourceCache<S> {
+    inner: S,
+    resources: Mutex<HashMap<(StateKey, TypeId), CachedResource>>,
+}
+
+impl<S: StateView> ResourceCache<S> {
+    pub fn new(inner: S) -> Self {
+        Self {
+            inner,
+            resources: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Get Resource by type R, a missing resource is cached too.
+    pub fn get_resource<R>(&self, address: AccountAddress) -> Result<Option<R>>
+    where
+        R: MoveResource + DeserializeOwned + Clone + Send + 'static,
+    {
+        let access_path = AccessPath::new(address, R::resource_path());
+        let key = (StateKey::AccessPath(access_path.clone()), TypeId::of::<R>());
+        if let Some(cached) = self
+            .resources
+            .lock()
+            .map_err(|e| format_err!("resource cache lock poisoned: {}", e))?
+            .get(&key)
+        {
+            return Ok(cached
+                .as_ref()
+                .and_then(|resource| resource.downcast_ref::<R>())
+                .cloned());
+        }
+        let resource = self.inner.get_resource_by_access_path::<R>(access_path)?;
+        self.resources
+            .lock()
+            .map_err(|e| format_err!("resource cache lock poisoned: {}", e))?
+            .insert(
+                key,
+                resource
+                    .clone()
+                    .map(|resource| Box::new(resource) as Box<dyn Any + Send>),
+            );
+        Ok(resource)
+    }
+
+    /// Drop all the cached resources, must be called when the inner view moves to another state.
+    pub fn invalidate(&self) {
+        if let Ok(mut resources) = self.resources.lock() {
+            resources.clear();
+        }
+    }
+
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S: StateView> StateView for ResourceCache<S> {
+    fn get_state_value(&self, state_key: &StateKey) -> Result<Option<Vec<u8>>> {
+        self.inner.get_state_value(state_key)
+    }
+
+    fn multi_get_state_value(&self, state_keys: &[StateKey]) -> Result<Vec<Option<Vec<u8>>>> {
+        self.inner.multi_get_state_value(state_keys)
+    }
+
+    fn is_genesis(&self) -> bool {
+        self.inner.is_genesis()
+    }
+}
+
+/// A `StateView` wrapper which caches the state values read through it, a missing value is cached
+/// too. The inner view must not change during the lifetime of the wrapper.
+pub struct CachedStateView<S> {
+    inner: S,
+    values: Mutex<HashMap<StateKey, Option<Vec<u8>>>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl<S: StateView> CachedStateView<S> {
+    pub fn new(inner: S) -> Self {
+        Self {
+            inner,
+            values: Mutex::new(HashMap::new()),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// The number of reads served from the cache.
+    pub fn hits(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    /// The number of reads forwarded to the inner view.
+    pub fn misses(&self) -> u64 {
+        self.misses.load(Ordering::Relaxed)
+    }
+
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+
+    fn values(&self) -> Result<MutexGuard<'_, HashMap<StateKey, Option<Vec<u8>>>>> {
+        self.values
+            .lock()
+            .map_err(|e| format_err!("cached state view lock poisoned: {}", e))
+    }
+}
+
+// the lock is released during the inner reads, so the concurrent reads of other keys are not
+// blocked by a slow inner view, a key read concurrently may be forwarded to the inner view twice.
+impl<S: StateView> StateView for CachedStateView<S> {
+    fn get_state_value(&self, state_key: &StateKey) -> Result<Option<Vec<u8>>> {
+        if let Some(value) = self.values()?.get(state_key) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(value.clone());
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let value = self.inner.get_state_value(state_key)?;
+        self.values()?.insert(state_key.clone(), value.clone());
+        Ok(value)
+    }
+
+    fn multi_get_state_value(&self, state_keys: &[StateKey]) -> Result<Vec<Option<Vec<u8>>>> {
+        let missing_keys: Vec<StateKey> = {
+            let values = self.values()?;
+            state_keys
+                .iter()
+                .filter(|state_key| !values.contains_key(*state_key))
+                .cloned()
+                .collect::<HashSet<_>>()
+                .into_iter()
+                .collect()
+        };
+        let missing_values = if missing_keys.is_empty() {
+            vec![]
+        } else {
+            self.inner.multi_get_state_value(&missing_keys)?
+        };
+        let mut values = self.values()?;
+        values.extend(missing_keys.iter().cloned().zip(missing_values));
+        let misses = missing_keys.len() as u64;
+        self.misses.fetch_add(misses, Ordering::Relaxed);
+        self.hits
+            .fetch_add(state_keys.len() as u64 - misses, Ordering::Relaxed);
+        Ok(state_keys
+            .iter()
+            .map(|state_key| values.get(state_key).cloned().flatten())
+            .collect())
+    }
+
+    fn is_genesis(&self) -> bool {
+        self.inner.is_genesis()
+    }
+}
+
+/// A `StateView` wrapper which fails the reads once more than `max_reads` state values or
+/// `max_bytes` bytes in total have been read through it.
+pub struct BudgetedStateView<S> {
+    inner: S,
+    max_bytes: u64,
+    max_reads: u64,
+    bytes: AtomicU64,
+    reads: AtomicU64,
+}
+
+impl<S: StateView> BudgetedStateView<S> {
+    pub fn new(inner: S, max_bytes: u64, max_reads: u64) -> Self {
+        Self {
+            inner,
+            max_bytes,
+            max_reads,
+            bytes: AtomicU64::new(0),
+            reads: AtomicU64::new(0),
+        }
+    }
+
+    /// The number of bytes read so far.
+    pub fn bytes_read(&self) -> u64 {
+        self.bytes.load(Ordering::Relaxed)
+    }
+
+    /// The number of state values read so far.
+    pub fn reads(&self) -> u64 {
+        self.reads.load(Ordering::Relaxed)
+    }
+}
+
+impl<S: StateView> StateView for BudgetedStateView<S> {
+    fn get_state_value(&self, state_key: &StateKey) -> Result<Option<Vec<u8>>> {
+        let reads = self.reads.fetch_add(1, Ordering::Relaxed) + 1;
+        if reads > self.max_reads {
+            bail!(
+                "State read count budget exceeded, max reads: {}",
+                self.max_reads
+            );
+        }
+        let value = self.inner.get_state_value(state_key)?;
+        let len = value.as_ref().map(|v| v.len() as u64).unwrap_or_default();
+        let bytes = self.bytes.fetch_add(len, Ordering::Relaxed) + len;
+        if bytes > self.max_bytes {
+            bail!(
+                "State read bytes budget exceeded, max bytes: {}",
+                self.max_bytes
+            );
+        }
+        Ok(value)
+    }
+
+    fn multi_get_state_value(&self, state_keys: &[StateKey]) -> Result<Vec<Option<Vec<u8>>>> {
+        let count = state_keys.len() as u64;
+        let reads = self.reads.fetch_add(count, Ordering::Relaxed) + count;
+        if reads > self.max_reads {
+            bail!(
+                "State read count budget exceeded, max reads: {}",
+                self.max_reads
+            );
+        }
+        let values = self.inner.multi_get_state_value(state_keys)?;
+        let len = values.iter().flatten().map(|v| v.len() as u64).sum::<u64>();
+        let bytes = self.bytes.fetch_add(len, Ordering::Relaxed) + len;
+        if bytes > self.max_bytes {
+            bail!(
+                "State read bytes budget exceeded, max bytes: {}",
+                self.max_bytes
+            );
+        }
+        Ok(values)
+    }
+
+    fn is_genesis(&self) -> bool {
+        self.inner.is_genesis()
+    }
+}
+
+/// A `StateView` wrapper which counts the bytes of the state values read through it, so the
+/// storage reads can be charged by size. An absent value counts as zero bytes.
+pub struct MeteredStateView<S> {
+    inner: S,
+    bytes: AtomicU64,
+}
+
+impl<S: StateView> MeteredStateView<S> {
+    pub fn new(inner: S) -> Self {
+        Self {
+            inner,
+            bytes: AtomicU64::new(0),
+        }
+    }
+
+    /// The number of bytes read so far.
+    pub fn bytes_read(&self) -> u64 {
+        self.bytes.load(Ordering::Relaxed)
+    }
+
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S: StateView> StateView for MeteredStateView<S> {
+    fn get_state_value(&self, state_key: &StateKey) -> Result<Option<Vec<u8>>> {
+        let value = self.inner.get_state_value(state_key)?;
+        let len = value.as_ref().map(|v| v.len() as u64).unwrap_or_default();
+        self.bytes.fetch_add(len, Ordering::Relaxed);
+        Ok(value)
+    }
+
+    fn multi_get_state_value(&self, state_keys: &[StateKey]) -> Result<Vec<Option<Vec<u8>>>> {
+        let values = self.inner.multi_get_state_value(state_keys)?;
+        let len = values.iter().flatten().map(|v| v.len() as u64).sum::<u64>();
+        self.bytes.fetch_add(len, Ordering::Relaxed);
+        Ok(values)
+    }
+
+    fn is_genesis(&self) -> bool {
+        self.inner.is_genesis()
+    }
+}
+
+/// A `StateView` over a map of state values, the keys not in the map are absent.
+#[derive(Clone, Debug, Default)]
+pub struct InMemoryStateView {
+    data: HashMap<StateKey, Vec<u8>>,
+    is_genesis: bool,
+}
+
+impl InMemoryStateView {
+    pub fn new(data: HashMap<StateKey, Vec<u8>>, is_genesis: bool) -> Self {
+        Self { data, is_genesis }
+    }
+
+    /// Preload the value of the state key.
+    pub fn with_state_value(mut self, state_key: StateKey, value: Vec<u8>) -> Self {
+        self.data.insert(state_key, value);
+        self
+    }
+
+    /// Preload the value of the access path.
+    pub fn with_access_path(self, access_path: AccessPath, value: Vec<u8>) -> Self {
+        self.with_state_value(StateKey::AccessPath(access_path), value)
+    }
+
+    pub fn with_genesis(mut self, is_genesis: bool) -> Self {
+        self.is_genesis = is_genesis;
+        self
+    }
+
+    pub fn data(&self) -> &HashMap<StateKey, Vec<u8>> {
+        &self.data
+    }
+}
+
+impl StateView for InMemoryStateView {
+    fn get_state_value(&self, state_key: &StateKey) -> Result<Option<Vec<u8>>> {
+        Ok(self.data.get(state_key).cloned())
+    }
+
+    fn multi_get_state_value(&self, state_keys: &[StateKey]) -> Result<Vec<Option<Vec<u8>>>> {
+        Ok(state_keys
+            .iter()
+            .map(|state_key| self.data.get(state_key).cloned())
+            .collect())
+    }
+
+    /// The accounts which have any access path in the map, in the order of their address.
+    fn iter_accounts(&self) -> Result<Box<dyn Iterator<Item = Result<AccountAddress>> + '_>> {
+        let accounts: BTreeSet<AccountAddress> = self
+            .data
+            .keys()
+            .filter_map(|state_key| match state_key {
+                StateKey::AccessPath(access_path) => Some(access_path.address),
+                StateKey::TableItem(_) => None,
+            })
+            .collect();
+        Ok(Box::new(accounts.into_iter().map(Ok)))
+    }
+
+    fn is_genesis(&self) -> bool {
+        self.is_genesis
+    }
+}
+
+/// A `StateView` which layers pending writes over a base view, a `None` in the overlay deletes the
+/// value of the base view.
+pub struct OverlayStateView<'a, S: ?Sized> {
+    base: &'a S,
+    overlay: HashMap<StateKey, Option<Vec<u8>>>,
+}
+
+impl<'a, S: StateView + ?Sized> OverlayStateView<'a, S> {
+    pub fn new(base: &'a S) -> Self {
+        Self {
+            base,
+            overlay: HashMap::new(),
+        }
+    }
+
+    /// Write the value of the key in the overlay.
+    pub fn insert(&mut self, state_key: StateKey, value: Vec<u8>) {
+        self.overlay.insert(state_key, Some(value));
+    }
+
+    /// Delete the key in the overlay, it is absent even if the base view has it.
+    pub fn delete(&mut self, state_key: StateKey) {
+        self.overlay.insert(state_key, None);
+    }
+
+    pub fn overlay(&self) -> &HashMap<StateKey, Option<Vec<u8>>> {
+        &self.overlay
+    }
+}
+
+impl<'a, S: StateView + ?Sized> StateView for OverlayStateView<'a, S> {
+    fn get_state_value(&self, state_key: &StateKey) -> Result<Option<Vec<u8>>> {
+        match self.overlay.get(state_key) {
+            Some(value) => Ok(value.clone()),
+            None => self.base.get_state_value(state_key),
+        }
+    }
+
+    fn multi_get_state_value(&self, state_keys: &[StateKey]) -> Result<Vec<Option<Vec<u8>>>> {
+        let base_keys: Vec<StateKey> = state_keys
+            .iter()
+            .filter(|state_key| !self.overlay.contains_key(*state_key))
+            .cloned()
+            .collect();
+        let mut base_values = self.base.multi_get_state_value(&base_keys)?.into_iter();
+        state_keys
+            .iter()
+            .map(|state_key| match self.overlay.get(state_key) {
+                Some(value) => Ok(value.clone()),
+                None => base_values
+                    .next()
+                    .ok_or_else(|| format_err!("Missing base state value of {:?}", state_key)),
+            })
+            .collect()
+    }
+
+    fn is_genesis(&self) -> bool {
+        self.base.is_genesis()
+    }
+}
+
+impl StateViewScan for InMemoryStateView {
+    fn iter_prefix(
+        &self,
+        prefix: &AccessPath,
+    ) -> Result<Box<dyn Iterator<Item = Result<(AccessPath, Vec<u8>)>> + '_>> {
+        let mut entries: Vec<(AccessPath, Vec<u8>)> = self
+            .data
+            .iter()
+            .filter_map(|(state_key, value)| match state_key {
+                StateKey::AccessPath(access_path)
+                    if access_path_has_prefix(access_path, prefix) =>
+                {
+                    Some((access_path.clone(), value.clone()))
+                }
+                _ => None,
+            })
+            .collect();
+        entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+        Ok(Box::new(entries.into_iter().map(Ok)))
+    }
+}
+
+/// A `StateView` wrapper which records every key read through it with the value returned,
+/// including the absent keys.
+pub struct RecordingStateView<'a, S: ?Sized> {
+    inner: &'a S,
+    reads: Mutex<HashMap<StateKey, Option<Vec<u8>>>>,
+}
+
+impl<'a, S: StateView + ?Sized> RecordingStateView<'a, S> {
+    pub fn new(inner: &'a S) -> Self {
+        Self {
+            inner,
+            reads: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Take the recorded reads.
+    pub fn into_reads(self) -> HashMap<StateKey, Option<Vec<u8>>> {
+        self.reads
+            .into_inner()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Take the keys read, with whether the key was present. The absent keys are kept too, a
+    /// later write to them conflicts with the reads.
+    pub fn into_read_set(self) -> HashMap<StateKey, bool> {
+        self.into_reads()
+            .into_iter()
+            .map(|(state_key, value)| (state_key, value.is_some()))
+            .collect()
+    }
+}
+
+impl<'a, S: StateView + ?Sized> StateView for RecordingStateView<'a, S> {
+    fn get_state_value(&self, state_key: &StateKey) -> Result<Option<Vec<u8>>> {
+        let value = self.inner.get_state_value(state_key)?;
+        self.reads
+            .lock()
+            .map_err(|e| format_err!("recording state view lock poisoned: {}", e))?
+            .insert(state_key.clone(), value.clone());
+        Ok(value)
+    }
+
+    fn multi_get_state_value(&self, state_keys: &[StateKey]) -> Result<Vec<Option<Vec<u8>>>> {
+        let values = self.inner.multi_get_state_value(state_keys)?;
+        let mut reads = self
+            .reads
+            .lock()
+            .map_err(|e| format_err!("recording state view lock poisoned: {}", e))?;
+        for (state_key, value) in state_keys.iter().zip(values.iter()) {
+            reads.insert(state_key.clone(), value.clone());
+        }
+        Ok(values)
+    }
+
+    fn is_genesis(&self) -> bool {
+        self.inner.is_genesis()
+    }
+}
+
+/// The storage reads saved by a `CachedStateView` during a block.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CacheSavingsReport {
+    /// The distinct keys read during the block.
+    pub distinct_keys: u64,
+    /// The reads served from the cache.
+    pub cached_reads: u64,
+    /// The reads forwarded to the storage.
+    pub storage_reads: u64,
+    /// The estimated bytes not read from the storage again.
+    pub saved_bytes: u64,
+    /// The estimated storage read gas of the `saved_bytes`.
+    pub saved_gas: u64,
+}
+
+/// Estimate the storage read gas saved by the `cache` during a block, `recording` wraps the cache
+/// and records the keys the block read. A cached read is priced at the average size of the
+/// recorded values, by the `global_memory_per_byte_cost`.
+pub fn block_cache_savings<R: StateView + ?Sized, C: StateView>(
+    recording: &RecordingStateView<'_, R>,
+    cache: &CachedStateView<C>,
+    gas_constants: &GasConstants,
+) -> CacheSavingsReport {
+    let (distinct_keys, recorded_bytes) = {
+        let reads = recording
+            .reads
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let recorded_bytes: u64 = reads
+            .values()
+            .map(|value| value.as_ref().map_or(0, |value| value.len() as u64))
+            .sum();
+        (reads.len() as u64, recorded_bytes)
+    };
+    let cached_reads = cache.hits();
+    let saved_bytes = if distinct_keys == 0 {
+        0
+    } else {
+        (cached_reads as u128 * recorded_bytes as u128 / distinct_keys as u128)
+            .min(u64::MAX as u128) as u64
+    };
+    CacheSavingsReport {
+        distinct_keys,
+        cached_reads,
+        storage_reads: cache.misses(),
+        saved_bytes,
+        saved_gas: saved_bytes.saturating_mul(gas_constants.global_memory_per_byte_cost),
+    }
+}
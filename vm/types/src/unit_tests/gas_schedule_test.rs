@@ -0,0 +1,163 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::gas_schedule::{
+    CostTable, GasPreset, G_GAS_CONSTANTS_V1, G_GAS_CONSTANTS_V2, G_GAS_CONSTANTS_V3,
+    G_LATEST_GAS_SCHEDULE,
+};
+use crate::on_chain_config::{
+    check_compatibility, instruction_gas_schedule_v2, instruction_table_v1, instruction_table_v2,
+    native_gas_schedule_v4, native_table_v1, native_table_v2, txn_gas_schedule_v3, v3_native_table,
+    v4_native_table, validate_native_coverage, GasSchedule,
+};
+use starcoin_gas_algebra_ext::GasCost;
+
+#[test]
+fn test_validate_native_coverage() {
+    validate_native_coverage(&G_LATEST_GAS_SCHEDULE).unwrap();
+
+    let mut cost_table = G_LATEST_GAS_SCHEDULE.clone();
+    cost_table.native_table.pop();
+    // the last registered native is reported by name.
+    let err = validate_native_coverage(&cost_table).unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("move_stdlib.string.index_of.per_byte_searched"));
+
+    // the v3 natives match the latest schedule by position, up to the first table native.
+    cost_table.native_table = v3_native_table();
+    let err = validate_native_coverage(&cost_table).unwrap_err();
+    assert!(err.to_string().contains("table.new_table_handle.base"));
+
+    // an entry without a registered native.
+    cost_table.native_table = v4_native_table();
+    cost_table.native_table.push(GasCost::new(1, 1));
+    assert!(validate_native_coverage(&cost_table).is_err());
+}
+
+#[test]
+fn test_gas_schedule_review_text() {
+    let mut entries = instruction_gas_schedule_v2();
+    entries.extend(native_gas_schedule_v4());
+    entries.extend(txn_gas_schedule_v3());
+    let gas_schedule = GasSchedule { entries };
+    let text = gas_schedule.to_review_text();
+    assert_eq!(text.lines().count(), gas_schedule.entries.len());
+
+    let mut reversed = gas_schedule.clone();
+    reversed.entries.reverse();
+    assert_eq!(reversed.to_review_text(), text);
+
+    let mut changed = gas_schedule;
+    changed.entries[0].1 += 1;
+    let changed_text = changed.to_review_text();
+    let diff = text
+        .lines()
+        .zip(changed_text.lines())
+        .filter(|(before, after)| before != after)
+        .count();
+    assert_eq!(diff, 1);
+}
+
+#[test]
+fn test_check_compatibility() {
+    let cost_table = |instruction_table, native_table, gas_constants| CostTable {
+        instruction_table,
+        native_table,
+        gas_constants,
+    };
+    let version_tables = vec![
+        (
+            1,
+            cost_table(
+                instruction_table_v1(),
+                native_table_v1(),
+                G_GAS_CONSTANTS_V1.clone(),
+            ),
+        ),
+        (
+            2,
+            cost_table(
+                instruction_table_v1(),
+                native_table_v2(),
+                G_GAS_CONSTANTS_V2.clone(),
+            ),
+        ),
+        (
+            3,
+            cost_table(
+                instruction_table_v2(),
+                v3_native_table(),
+                G_GAS_CONSTANTS_V3.clone(),
+            ),
+        ),
+        (
+            4,
+            cost_table(
+                instruction_table_v2(),
+                v4_native_table(),
+                G_GAS_CONSTANTS_V3.clone(),
+            ),
+        ),
+    ];
+    for (version, table) in version_tables.iter() {
+        check_compatibility(table, *version).unwrap();
+        // every version has different table dimensions.
+        for (other_version, _) in version_tables.iter() {
+            if other_version != version {
+                assert!(check_compatibility(table, *other_version).is_err());
+            }
+        }
+    }
+    check_compatibility(&G_LATEST_GAS_SCHEDULE, 4).unwrap();
+    assert!(check_compatibility(&G_LATEST_GAS_SCHEDULE, 0).is_err());
+
+    // a native table longer than the registry is an error rather than a panic.
+    let mut table = G_LATEST_GAS_SCHEDULE.clone();
+    table.native_table.push(GasCost::new(1, 1));
+    assert!(check_compatibility(&table, 4).is_err());
+}
+
+#[test]
+fn test_cost_table_json() {
+    let json = G_LATEST_GAS_SCHEDULE.to_json().unwrap();
+    assert_eq!(CostTable::from_json(&json).unwrap(), *G_LATEST_GAS_SCHEDULE);
+    // the constants are plain integers.
+    assert!(json.contains(&format!(
+        "\"max_price_per_gas_unit\": {}",
+        G_LATEST_GAS_SCHEDULE.gas_constants.max_price_per_gas_unit
+    )));
+
+    let json = r#"{
+        "instruction_table": [{"instruction_gas": 1, "memory_gas": 1}],
+        "native_table": [],
+        "gas_constants": {
+            "global_memory_per_byte_cost": 4,
+            "global_memory_per_byte_write_cost": 9
+        }
+    }"#;
+    let err = CostTable::from_json(json).unwrap_err().to_string();
+    assert!(
+        err.contains("missing field `min_transaction_gas_units`"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn test_gas_presets() {
+    // the intrinsic gas and the storage write of one account.
+    let workload = |cost_table: &CostTable| {
+        let constants = &cost_table.gas_constants;
+        constants.min_transaction_gas_units
+            + constants.global_memory_per_byte_write_cost * constants.default_account_size
+    };
+    for preset in GasPreset::ALL {
+        let cost_table = preset.cost_table();
+        cost_table.gas_constants.validate().unwrap();
+        check_compatibility(&cost_table, 4).unwrap();
+        assert_eq!(preset.to_string(), preset.name());
+    }
+    assert_eq!(GasPreset::Main.cost_table(), *G_LATEST_GAS_SCHEDULE);
+    assert!(workload(&GasPreset::Test.cost_table()) < workload(&GasPreset::Main.cost_table()));
+}
@@ -3,4 +3,6 @@
 
 mod access_path_test;
 mod block_metadata_test;
+mod gas_schedule_test;
+mod state_view_test;
 mod transaction_test;
@@ -0,0 +1,429 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::access_path::AccessPath;
+use crate::account_address::AccountAddress;
+use crate::account_config::{genesis_address, AccountResource};
+use crate::gas_schedule::G_LATEST_GAS_CONSTANTS;
+use crate::genesis_config::ChainId;
+use crate::identifier::Identifier;
+use crate::move_resource::MoveResource;
+use crate::state_store::state_key::StateKey;
+use crate::state_view::{
+    access_path_has_prefix, block_cache_savings, validate_access_path, BudgetedStateView,
+    CachedStateView, InMemoryStateView, MeteredStateView, OverlayStateView, RecordingStateView,
+    ResourceCache, StateReaderExt, StateView, StateViewScan, MAX_ACCESS_PATH_TYPE_DEPTH,
+};
+use anyhow::Result;
+use move_core_types::language_storage::{StructTag, TypeTag};
+use std::cell::Cell;
+use std::collections::HashMap;
+
+#[derive(Default)]
+struct MockStateView {
+    data: HashMap<StateKey, Vec<u8>>,
+    reads: Cell<usize>,
+}
+
+impl StateView for MockStateView {
+    fn get_state_value(&self, state_key: &StateKey) -> Result<Option<Vec<u8>>> {
+        self.reads.set(self.reads.get() + 1);
+        Ok(self.data.get(state_key).cloned())
+    }
+
+    fn is_genesis(&self) -> bool {
+        false
+    }
+}
+
+fn nested_vector_struct_tag(depth: usize) -> StructTag {
+    let mut type_tag = TypeTag::U8;
+    for _ in 0..depth {
+        type_tag = TypeTag::Vector(Box::new(type_tag));
+    }
+    StructTag {
+        address: AccountAddress::random(),
+        module: Identifier::new("Test").unwrap(),
+        name: Identifier::new("Test").unwrap(),
+        type_params: vec![type_tag],
+    }
+}
+
+#[test]
+fn test_validate_valid_access_path() {
+    let state_view = MockStateView::default();
+    let access_path = AccessPath::resource_access_path(
+        AccountAddress::random(),
+        nested_vector_struct_tag(MAX_ACCESS_PATH_TYPE_DEPTH - 1),
+    );
+    assert!(validate_access_path(&access_path).is_ok());
+    assert!(validate_access_path(&AccessPath::random_code()).is_ok());
+    assert!(state_view
+        .get_resource_by_access_path::<AccountResource>(access_path)
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn test_validate_malformed_access_path() {
+    let state_view = MockStateView::default();
+    let access_path = AccessPath::resource_access_path(
+        AccountAddress::random(),
+        nested_vector_struct_tag(MAX_ACCESS_PATH_TYPE_DEPTH + 1),
+    );
+    let err = validate_access_path(&access_path).unwrap_err();
+    assert!(err.to_string().contains("nested deeper"));
+    // the reads are on the VM path and do not validate, the RPC does.
+    assert!(state_view
+        .get_resource_by_access_path::<AccountResource>(access_path)
+        .unwrap()
+        .is_none());
+
+    // the struct type parameters count to the depth too.
+    let mut struct_tag = nested_vector_struct_tag(0);
+    for _ in 0..MAX_ACCESS_PATH_TYPE_DEPTH {
+        let mut outer = nested_vector_struct_tag(0);
+        outer.type_params = vec![TypeTag::Struct(Box::new(struct_tag))];
+        struct_tag = outer;
+    }
+    let access_path = AccessPath::resource_access_path(AccountAddress::random(), struct_tag);
+    let err = validate_access_path(&access_path).unwrap_err();
+    assert!(err.to_string().contains("nested deeper"));
+}
+
+#[test]
+fn test_resource_cache() {
+    let address = AccountAddress::random();
+    let mut state_view = MockStateView::default();
+    state_view.data.insert(
+        StateKey::AccessPath(AccessPath::new(address, ChainId::resource_path())),
+        bcs_ext::to_bytes(&ChainId::new(1)).unwrap(),
+    );
+    let cache = ResourceCache::new(state_view);
+
+    assert_eq!(
+        cache.get_resource::<ChainId>(address).unwrap(),
+        Some(ChainId::new(1))
+    );
+    assert_eq!(
+        cache.get_resource::<ChainId>(address).unwrap(),
+        Some(ChainId::new(1))
+    );
+    let missing = AccountAddress::random();
+    assert!(cache.get_resource::<ChainId>(missing).unwrap().is_none());
+    assert!(cache.get_resource::<ChainId>(missing).unwrap().is_none());
+    cache.invalidate();
+    assert!(cache.get_resource::<ChainId>(address).unwrap().is_some());
+
+    let state_view = cache.into_inner();
+    assert_eq!(state_view.reads.get(), 3);
+}
+
+fn state_view_with_values(count: usize, value_len: usize) -> (MockStateView, Vec<StateKey>) {
+    let mut state_view = MockStateView::default();
+    let keys: Vec<StateKey> = (0..count)
+        .map(|_| StateKey::AccessPath(AccessPath::random_resource()))
+        .collect();
+    for key in &keys {
+        state_view.data.insert(key.clone(), vec![0u8; value_len]);
+    }
+    (state_view, keys)
+}
+
+#[test]
+fn test_budgeted_state_view_read_budget() {
+    let (state_view, keys) = state_view_with_values(3, 10);
+    let budgeted = BudgetedStateView::new(state_view, 1000, 2);
+    assert!(budgeted.get_state_value(&keys[0]).unwrap().is_some());
+    assert!(budgeted.get_state_value(&keys[1]).unwrap().is_some());
+    let err = budgeted.get_state_value(&keys[2]).unwrap_err();
+    assert!(err.to_string().contains("read count budget exceeded"));
+    assert_eq!(budgeted.bytes_read(), 20);
+}
+
+#[test]
+fn test_budgeted_state_view_bytes_budget() {
+    let (state_view, keys) = state_view_with_values(3, 10);
+    let budgeted = BudgetedStateView::new(state_view, 25, 100);
+    assert!(budgeted.get_state_value(&keys[0]).unwrap().is_some());
+    assert!(budgeted.get_state_value(&keys[1]).unwrap().is_some());
+    let err = budgeted.get_state_value(&keys[2]).unwrap_err();
+    assert!(err.to_string().contains("read bytes budget exceeded"));
+    assert_eq!(budgeted.reads(), 3);
+}
+
+#[test]
+fn test_multi_get_state_value() {
+    let (mut state_view, keys) = state_view_with_values(2, 10);
+    state_view.data.insert(keys[1].clone(), vec![1u8; 5]);
+    let missing = StateKey::AccessPath(AccessPath::random_resource());
+    let state_keys = vec![keys[1].clone(), missing, keys[0].clone()];
+    assert_eq!(
+        state_view.multi_get_state_value(&state_keys).unwrap(),
+        vec![Some(vec![1u8; 5]), None, Some(vec![0u8; 10])]
+    );
+    assert_eq!(state_view.reads.get(), 3);
+
+    let budgeted = BudgetedStateView::new(state_view, 1000, 3);
+    assert_eq!(
+        budgeted.multi_get_state_value(&state_keys).unwrap().len(),
+        3
+    );
+    assert_eq!(budgeted.reads(), 3);
+    assert_eq!(budgeted.bytes_read(), 15);
+    let err = budgeted.multi_get_state_value(&keys[..1]).unwrap_err();
+    assert!(err.to_string().contains("read count budget exceeded"));
+}
+
+#[test]
+fn test_cached_state_view() {
+    let (state_view, keys) = state_view_with_values(2, 10);
+    let cached = CachedStateView::new(state_view);
+    let missing = StateKey::AccessPath(AccessPath::random_resource());
+
+    assert_eq!(
+        cached.get_state_value(&keys[0]).unwrap(),
+        Some(vec![0u8; 10])
+    );
+    assert_eq!(
+        cached.get_state_value(&keys[0]).unwrap(),
+        Some(vec![0u8; 10])
+    );
+    assert!(cached.get_state_value(&missing).unwrap().is_none());
+    assert!(cached.get_state_value(&missing).unwrap().is_none());
+    assert_eq!(cached.hits(), 2);
+    assert_eq!(cached.misses(), 2);
+
+    let state_keys = vec![keys[1].clone(), missing, keys[0].clone(), keys[1].clone()];
+    assert_eq!(
+        cached.multi_get_state_value(&state_keys).unwrap(),
+        vec![
+            Some(vec![0u8; 10]),
+            None,
+            Some(vec![0u8; 10]),
+            Some(vec![0u8; 10])
+        ]
+    );
+    assert_eq!(cached.hits(), 5);
+    assert_eq!(cached.misses(), 3);
+
+    let state_view = cached.into_inner();
+    assert_eq!(state_view.reads.get(), 3);
+}
+
+#[test]
+fn test_block_cache_savings() {
+    let (state_view, keys) = state_view_with_values(1, 10);
+    let cached = CachedStateView::new(state_view);
+    let recorder = RecordingStateView::new(&cached);
+    for _ in 0..10 {
+        assert!(recorder.get_state_value(&keys[0]).unwrap().is_some());
+    }
+
+    let report = block_cache_savings(&recorder, &cached, &G_LATEST_GAS_CONSTANTS);
+    assert_eq!(report.distinct_keys, 1);
+    assert_eq!(report.cached_reads, 9);
+    assert_eq!(report.storage_reads, 1);
+    assert_eq!(report.saved_bytes, 90);
+    assert_eq!(
+        report.saved_gas,
+        90 * G_LATEST_GAS_CONSTANTS.global_memory_per_byte_cost
+    );
+    assert!(report.saved_gas > 0);
+}
+
+#[test]
+fn test_overlay_state_view() {
+    let (state_view, keys) = state_view_with_values(3, 10);
+    let new_key = StateKey::AccessPath(AccessPath::random_resource());
+    let mut overlay = OverlayStateView::new(&state_view);
+    overlay.insert(keys[0].clone(), vec![1u8; 5]);
+    overlay.insert(new_key.clone(), vec![2u8; 5]);
+    overlay.delete(keys[1].clone());
+
+    assert_eq!(
+        overlay.get_state_value(&keys[0]).unwrap(),
+        Some(vec![1u8; 5])
+    );
+    assert_eq!(
+        overlay.get_state_value(&new_key).unwrap(),
+        Some(vec![2u8; 5])
+    );
+    assert!(overlay.get_state_value(&keys[1]).unwrap().is_none());
+    assert_eq!(
+        overlay.get_state_value(&keys[2]).unwrap(),
+        Some(vec![0u8; 10])
+    );
+    assert_eq!(state_view.reads.get(), 1);
+
+    let state_keys = vec![keys[2].clone(), keys[1].clone(), keys[0].clone()];
+    assert_eq!(
+        overlay.multi_get_state_value(&state_keys).unwrap(),
+        vec![Some(vec![0u8; 10]), None, Some(vec![1u8; 5])]
+    );
+    assert_eq!(state_view.reads.get(), 2);
+    assert_eq!(
+        state_view.get_state_value(&keys[1]).unwrap(),
+        Some(vec![0u8; 10])
+    );
+}
+
+#[test]
+fn test_in_memory_state_view() {
+    let address = AccountAddress::random();
+    let other_address = AccountAddress::random();
+    let access_path = AccessPath::new(address, ChainId::resource_path());
+    let other_key = StateKey::AccessPath(AccessPath::new(
+        other_address,
+        AccountResource::resource_path(),
+    ));
+    let missing = StateKey::AccessPath(AccessPath::random_resource());
+    let state_view = InMemoryStateView::default()
+        .with_access_path(access_path.clone(), vec![1u8; 5])
+        .with_state_value(other_key.clone(), vec![2u8; 5]);
+    assert!(!state_view.is_genesis());
+    assert!(state_view.clone().with_genesis(true).is_genesis());
+
+    let key = StateKey::AccessPath(access_path);
+    assert_eq!(
+        state_view.get_state_value(&key).unwrap(),
+        Some(vec![1u8; 5])
+    );
+    assert!(state_view.get_state_value(&missing).unwrap().is_none());
+    assert_eq!(
+        state_view
+            .multi_get_state_value(&[other_key, missing, key])
+            .unwrap(),
+        vec![Some(vec![2u8; 5]), None, Some(vec![1u8; 5])]
+    );
+    let mut expected = vec![address, other_address];
+    expected.sort();
+    let accounts = state_view
+        .iter_accounts()
+        .unwrap()
+        .collect::<Result<Vec<_>>>()
+        .unwrap();
+    assert_eq!(accounts, expected);
+}
+
+#[test]
+fn test_in_memory_state_view_iter_prefix() {
+    let address = AccountAddress::random();
+    let balance_path = |token: &str| {
+        AccessPath::resource_access_path(
+            address,
+            StructTag {
+                address: genesis_address(),
+                module: Identifier::new("Account").unwrap(),
+                name: Identifier::new("Balance").unwrap(),
+                type_params: vec![TypeTag::Struct(Box::new(StructTag {
+                    address: genesis_address(),
+                    module: Identifier::new(token).unwrap(),
+                    name: Identifier::new(token).unwrap(),
+                    type_params: vec![],
+                }))],
+            },
+        )
+    };
+    let prefix = AccessPath::resource_access_path(
+        address,
+        StructTag {
+            address: genesis_address(),
+            module: Identifier::new("Account").unwrap(),
+            name: Identifier::new("Balance").unwrap(),
+            type_params: vec![],
+        },
+    );
+    let state_view = InMemoryStateView::default()
+        .with_access_path(balance_path("STC"), vec![1u8])
+        .with_access_path(balance_path("XUSDT"), vec![2u8])
+        .with_access_path(
+            AccessPath::new(address, AccountResource::resource_path()),
+            vec![3u8],
+        )
+        .with_access_path(
+            AccessPath::code_access_path(address, Identifier::new("Balance").unwrap()),
+            vec![4u8],
+        )
+        .with_access_path(
+            AccessPath::resource_access_path(
+                AccountAddress::random(),
+                prefix.path.as_struct_tag().unwrap().clone(),
+            ),
+            vec![5u8],
+        );
+
+    let entries = state_view
+        .iter_prefix(&prefix)
+        .unwrap()
+        .collect::<Result<Vec<_>>>()
+        .unwrap();
+    let mut expected = vec![
+        (balance_path("STC"), vec![1u8]),
+        (balance_path("XUSDT"), vec![2u8]),
+    ];
+    expected.sort();
+    assert_eq!(entries, expected);
+    assert!(entries
+        .iter()
+        .all(|(access_path, _)| access_path_has_prefix(access_path, &prefix)));
+}
+
+#[test]
+fn test_recording_state_view_read_set() {
+    let (state_view, keys) = state_view_with_values(3, 10);
+    let missing = StateKey::AccessPath(AccessPath::random_resource());
+    let recorder = RecordingStateView::new(&state_view);
+    assert!(recorder.get_state_value(&keys[0]).unwrap().is_some());
+    assert!(recorder.get_state_value(&keys[0]).unwrap().is_some());
+    assert!(recorder.get_state_value(&missing).unwrap().is_none());
+    assert_eq!(
+        recorder
+            .multi_get_state_value(&[keys[1].clone(), missing.clone()])
+            .unwrap(),
+        vec![Some(vec![0u8; 10]), None]
+    );
+
+    let expected: HashMap<StateKey, bool> = vec![
+        (keys[0].clone(), true),
+        (keys[1].clone(), true),
+        (missing, false),
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(recorder.into_read_set(), expected);
+}
+
+#[test]
+fn test_metered_state_view() {
+    let (mut state_view, keys) = state_view_with_values(3, 10);
+    state_view.data.insert(keys[2].clone(), vec![0u8; 7]);
+    let metered = MeteredStateView::new(state_view);
+    assert!(metered.get_state_value(&keys[0]).unwrap().is_some());
+    assert!(metered.get_state_value(&keys[0]).unwrap().is_some());
+    assert_eq!(metered.bytes_read(), 20);
+    assert_eq!(
+        metered
+            .multi_get_state_value(&[keys[1].clone(), keys[2].clone()])
+            .unwrap()
+            .len(),
+        2
+    );
+    assert_eq!(metered.bytes_read(), 37);
+}
+
+#[test]
+fn test_metered_state_view_missing_key() {
+    let (state_view, keys) = state_view_with_values(1, 10);
+    let metered = MeteredStateView::new(state_view);
+    let missing = StateKey::AccessPath(AccessPath::random_resource());
+    assert!(metered.get_state_value(&missing).unwrap().is_none());
+    assert_eq!(metered.bytes_read(), 0);
+    assert_eq!(
+        metered
+            .multi_get_state_value(&[missing, keys[0].clone()])
+            .unwrap(),
+        vec![None, Some(vec![0u8; 10])]
+    );
+    assert_eq!(metered.bytes_read(), 10);
+}
@@ -83,6 +83,42 @@ impl<'block, S: StateView> StateView for StateViewCache<'block, S> {
         }
     }
 
+    // Serve the cached keys from the cache and read the misses from the `StateView` in one batch.
+    fn multi_get_state_value(
+        &self,
+        state_keys: &[StateKey],
+    ) -> anyhow::Result<Vec<Option<Vec<u8>>>> {
+        let mut values = Vec::with_capacity(state_keys.len());
+        let mut missing_keys = vec![];
+        let mut missing_positions = vec![];
+        for (position, state_key) in state_keys.iter().enumerate() {
+            match self.data_map.get(state_key) {
+                Some(opt_data) => values.push(opt_data.clone()),
+                None => {
+                    values.push(None);
+                    missing_keys.push(state_key.clone());
+                    missing_positions.push(position);
+                }
+            }
+        }
+        if !missing_keys.is_empty() {
+            let remote_values = self
+                .data_view
+                .multi_get_state_value(&missing_keys)
+                .map_err(|e| {
+                    error!(
+                        "[VM] Error getting data from storage for {:?}",
+                        missing_keys
+                    );
+                    e
+                })?;
+            for (position, remote_data) in missing_positions.into_iter().zip(remote_values) {
+                values[position] = remote_data;
+            }
+        }
+        Ok(values)
+    }
+
     fn is_genesis(&self) -> bool {
         self.data_view.is_genesis()
     }
@@ -12,11 +12,12 @@ use move_core_types::gas_algebra::{InternalGasPerByte, NumBytes};
 use move_table_extension::NativeTableContext;
 use move_vm_runtime::move_vm_adapter::{PublishModuleBundleOption, SessionAdapter};
 use move_vm_runtime::session::Session;
+use once_cell::sync::Lazy;
 use starcoin_config::genesis_config::G_LATEST_GAS_PARAMS;
 use starcoin_crypto::HashValue;
 use starcoin_gas::{NativeGasParameters, StarcoinGasMeter, StarcoinGasParameters};
 use starcoin_gas_algebra_ext::{
-    CostTable, FromOnChainGasSchedule, Gas, GasConstants, GasCost, InitialGasSchedule,
+    from_on_chain_gas_schedule_strict, CostTable, Gas, GasConstants, GasCost, InitialGasSchedule,
 };
 use starcoin_logger::prelude::*;
 use starcoin_types::account_config::config_change::ConfigChangeEvent;
@@ -63,11 +64,15 @@ use starcoin_vm_types::{
     values::Value,
     vm_status::{StatusCode, VMStatus},
 };
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 #[cfg(feature = "metrics")]
 use crate::metrics::VMMetrics;
 
+/// The gas constants last validated by `load_configs`, a VM is created for every block and txn
+/// validation, so the invalid constants are only warned once until the on-chain ones change.
+static G_CHECKED_GAS_CONSTANTS: Lazy<Mutex<Option<GasConstants>>> = Lazy::new(|| Mutex::new(None));
+
 #[derive(Clone)]
 #[allow(clippy::upper_case_acronyms)]
 /// Wrapper of MoveVM
@@ -128,10 +133,44 @@ impl StarcoinVM {
             self.load_configs_impl(state)?;
         }
         if let Some(ref vm_config) = self.vm_config {
+            // the on-chain schedule is used even if it is invalid, so all the nodes replay the same.
+            let gas_constants = &vm_config.gas_schedule.gas_constants;
+            let mut checked = G_CHECKED_GAS_CONSTANTS.lock().unwrap();
+            if checked.as_ref() != Some(gas_constants) {
+                *checked = Some(gas_constants.clone());
+                if let Err(e) = gas_constants.validate() {
+                    warn!(
+                        "[VM] Invalid gas constants in the on-chain gas schedule: {}",
+                        e
+                    );
+                }
+            }
+            drop(checked);
             let gas_schedule = GasSchedule::from(vm_config);
-            let gas_params =
-                StarcoinGasParameters::from_on_chain_gas_schedule(&gas_schedule.to_btree_map());
+            let gas_params = match from_on_chain_gas_schedule_strict::<StarcoinGasParameters>(
+                &gas_schedule.to_btree_map(),
+            ) {
+                Ok(params) => Some(params),
+                Err(e) => {
+                    // keep the gas parameters loaded before.
+                    warn!(
+                        "[VM] Load gas parameters from the gas schedule failed: {}",
+                        e
+                    );
+                    None
+                }
+            };
             if let Some(ref params) = gas_params {
+                let loaded_version = self
+                    .gas_params
+                    .as_ref()
+                    .map(|loaded| loaded.gas_schedule_version());
+                if loaded_version != Some(params.gas_schedule_version()) {
+                    info!(
+                        "[VM] Load gas schedule version {}",
+                        params.gas_schedule_version()
+                    );
+                }
                 if params.natives != self.native_params {
                     debug!("update native_params");
                     Arc::get_mut(&mut self.move_vm)
@@ -731,6 +770,8 @@ impl StarcoinVM {
         txn_data: &TransactionMetadata,
         success: bool,
     ) -> Result<(), VMStatus> {
+        // the fee is charged from the balance, so the reserved gas goes back to it first.
+        gas_meter.release_reserve();
         let genesis_address = genesis_address();
         let gas_token_ty =
             TypeTag::Struct(Box::new(txn_data.gas_token_code().try_into().map_err(